@@ -2,7 +2,11 @@ use std::path::PathBuf;
 
 use argh::FromArgs;
 
-use crate::{flashcards::Set, load_set};
+use crate::{
+    flashcards::{Set, Side},
+    load_set,
+    output::{self, Repeat},
+};
 
 /// Debug a flashcard set
 #[derive(Debug, FromArgs)]
@@ -11,11 +15,67 @@ pub struct Entry {
     /// the set to debug
     #[argh(positional)]
     set: PathBuf,
+    /// check the set for likely mistakes instead of printing it
+    #[argh(switch)]
+    validate: bool,
+    /// print one line for each card instead of the whole set
+    #[argh(switch)]
+    list: bool,
+    /// when validating, warn about terms or definitions shorter than this (default 2)
+    #[argh(option, default = "2")]
+    min_term_len: usize,
 }
 
 impl Entry {
     pub fn run(self) {
         let set = load_set!(&self.set);
-        dbg!(set);
+        if self.validate {
+            self.validate(&set);
+        } else if self.list {
+            list(&set);
+        } else {
+            dbg!(set);
+        }
+    }
+
+    fn validate(&self, set: &Set) {
+        let problems = set.problems(self.min_term_len);
+        for problem in &problems {
+            output::write_warning(&problem.to_string());
+        }
+        match problems.len() {
+            0 => println!("No problems found"),
+            1 => println!("Found 1 problem"),
+            problems => println!("Found {problems} problems"),
+        }
+    }
+}
+
+/// Prints each card as `term | definition (+N alts)`, with the terms padded so the
+/// definitions line up.  Alts counts every value but the first on each side, except
+/// blank answers
+fn list(set: &Set) {
+    let width = set
+        .cards
+        .iter()
+        .map(|card| card[Side::Term].canonical().chars().count())
+        .max()
+        .unwrap_or(0);
+    for card in &set.cards {
+        let term = card[Side::Term].canonical();
+        let padding = Repeat(' ', (width - term.chars().count()) as u16);
+        let definition = card[Side::Definition].canonical();
+        let alts: usize = [Side::Term, Side::Definition]
+            .map(|side| {
+                let accepted = card[side].other_accepted().iter();
+                card[side].displayable().len().saturating_sub(1)
+                    + accepted.filter(|value| !value.is_empty()).count()
+            })
+            .iter()
+            .sum();
+        match alts {
+            0 => println!("{term}{padding} | {definition}"),
+            alts => println!("{term}{padding} | {definition} (+{alts} alts)"),
+        }
     }
 }
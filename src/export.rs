@@ -0,0 +1,89 @@
+use std::{fmt::Write, fs, path::PathBuf, str::FromStr};
+
+use argh::FromArgs;
+
+use crate::{
+    flashcards::{Set, Side},
+    load_set, output,
+};
+
+/// Export a flashcard set for use in another app
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "export")]
+pub struct Entry {
+    /// the set to export
+    #[argh(positional)]
+    set: PathBuf,
+    /// the format to export to.  Only "anki" is supported
+    #[argh(option)]
+    export: Format,
+    /// the file to write to
+    #[argh(option, short = 'o')]
+    output: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    /// A tab separated text file with 4 fields per note: the term, the definition,
+    /// and the other accepted terms and definitions
+    Anki,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "anki" => Ok(Format::Anki),
+            _ => Err("expected \"anki\"".to_owned()),
+        }
+    }
+}
+
+impl Entry {
+    pub fn run(self) {
+        let set = load_set!(&self.set);
+        let text = match self.export {
+            Format::Anki => to_anki(&set),
+        };
+        if let Err(err) = fs::write(&self.output, text) {
+            output::write_fatal_error(&format!("Unable to write {}: {err}", self.output.display()));
+        }
+    }
+}
+
+fn to_anki(set: &Set) -> String {
+    let mut text = String::from("#separator:tab\n#html:false\n");
+    if let Some(title) = &set.metadata.title {
+        // Headers end at the line break, so the deck name can't contain one
+        writeln!(text, "#deck:{}", title.replace(['\r', '\n'], " ")).unwrap();
+    }
+    for card in &set.cards {
+        let fields = [Side::Term, Side::Definition]
+            .map(|side| card[side].displayable().join(", "))
+            .into_iter()
+            .chain([Side::Term, Side::Definition].map(|side| {
+                // Anki can't accept a blank answer, so leave those out
+                card[side]
+                    .other_accepted()
+                    .iter()
+                    .filter(|value| !value.is_empty())
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }))
+            .map(|field| anki_field(&field))
+            .collect::<Vec<_>>();
+        writeln!(text, "{}", fields.join("\t")).unwrap();
+    }
+    text
+}
+
+/// Quotes `field` if it has characters Anki would otherwise treat as separators
+fn anki_field(field: &str) -> String {
+    if field.contains(['\t', '\n', '"']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
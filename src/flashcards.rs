@@ -1,4 +1,5 @@
 use std::{
+    borrow::Cow,
     fmt::{Debug, Display, Write},
     fs,
     ops::{Index, IndexMut, Not},
@@ -7,16 +8,24 @@ use std::{
 };
 
 use crossterm::style::Color;
-use rand::seq::SliceRandom;
+use rand::{seq::SliceRandom, Rng};
 use smallvec::{smallvec, SmallVec};
 
-use crate::output;
+use crate::{
+    output::{self, TextAlignH},
+    vec2::Vec2,
+};
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct Set {
+    pub metadata: Metadata,
     pub recall_t: RecallSettings,
     pub recall_d: RecallSettings,
+    pub flashcards: FlashcardsSettings,
+    pub rtl: RtlSettings,
     pub cards: Vec<Flashcard>,
+    /// Problems found while parsing that don't stop the set from loading
+    pub warnings: Vec<ParseMetadataWarning>,
 }
 
 impl Set {
@@ -24,19 +33,117 @@ impl Set {
     /// be loaded
     pub fn load_from_file_path(path: &Path) -> Option<Self> {
         match fs::read_to_string(path) {
-            Ok(f) => match Set::from_str(&f) {
-                Ok(set) => Some(set),
-                Err(errors) => {
-                    let mut s = String::new();
-                    for error in errors {
-                        writeln!(s, "{error}").unwrap();
-                    }
-                    output::write_fatal_error(&s);
+            Ok(f) => Self::parse_printing_errors(&f),
+            Err(err) => {
+                output::write_fatal_error(&format!("Unable to open set: {err}"));
+                None
+            }
+        }
+    }
+
+    /// Downloads the set from the url specified, printing error information if it
+    /// cannot be loaded
+    #[cfg(feature = "fetch")]
+    pub fn load_from_url(url: &str) -> Option<Self> {
+        match ureq::get(url).call() {
+            Ok(response) => match response.into_string() {
+                Ok(f) => Self::parse_printing_errors(&f),
+                Err(err) => {
+                    output::write_fatal_error(&format!("Unable to read set: {err}"));
                     None
                 }
             },
+            Err(ureq::Error::Status(code, response)) => {
+                output::write_fatal_error(&format!(
+                    "Unable to fetch set: server responded with {code} {}",
+                    response.status_text()
+                ));
+                None
+            }
             Err(err) => {
-                output::write_fatal_error(&format!("Unable to open set: {err}"));
+                output::write_fatal_error(&format!("Unable to fetch set: {err}"));
+                None
+            }
+        }
+    }
+
+    #[cfg(not(feature = "fetch"))]
+    pub fn load_from_url(_url: &str) -> Option<Self> {
+        output::write_fatal_error(
+            "Unable to fetch set: efc was built without the \"fetch\" feature",
+        );
+        None
+    }
+
+    /// The settings for recalling `side` of a card
+    pub fn recall(&self, side: Side) -> RecallSettings {
+        match side {
+            Side::Term => self.recall_t,
+            Side::Definition => self.recall_d,
+        }
+    }
+
+    /// Returns each card with its index if recalling `side` is enabled, or nothing
+    /// if it isn't
+    pub fn cards_for_side(&self, side: Side) -> impl Iterator<Item = (usize, &Flashcard)> {
+        let count = match self.recall(side).is_used() {
+            true => self.cards.len(),
+            false => 0,
+        };
+        self.cards.iter().enumerate().take(count)
+    }
+
+    /// Finds likely mistakes in this set that don't stop it from loading.  Values
+    /// shorter than `min_term_len` characters are reported as too short
+    pub fn problems(&self, min_term_len: usize) -> Vec<SetProblem> {
+        let mut problems = Vec::new();
+        if !self.recall_t.is_used() && !self.recall_d.is_used() {
+            problems.push(SetProblem::NoRecall);
+        }
+        for (index, card) in self.cards.iter().enumerate() {
+            for side in [Side::Term, Side::Definition] {
+                for value in card[side].displayable() {
+                    if value.chars().count() < min_term_len {
+                        problems.push(SetProblem::ShortValue {
+                            card: index,
+                            side,
+                            value: value.clone(),
+                        });
+                    }
+                }
+            }
+            if card[Side::Term]
+                .displayable()
+                .iter()
+                .any(|term| card[Side::Definition].displayable().contains(term))
+            {
+                problems.push(SetProblem::SameSides { card: index });
+            }
+            let term = card[Side::Term].displayable();
+            if let Some(first) = self.cards[..index]
+                .iter()
+                .position(|other| other[Side::Term].displayable() == term)
+            {
+                problems.push(SetProblem::DuplicateTerm { card: index, first });
+            }
+        }
+        problems
+    }
+
+    fn parse_printing_errors(s: &str) -> Option<Self> {
+        match Set::from_str(s) {
+            Ok(set) => {
+                for warning in &set.warnings {
+                    output::write_warning(&warning.to_string());
+                }
+                Some(set)
+            }
+            Err(errors) => {
+                let mut s = String::new();
+                for error in errors {
+                    writeln!(s, "{error}").unwrap();
+                }
+                output::write_fatal_error(&s);
                 None
             }
         }
@@ -60,6 +167,8 @@ impl FromStr for Set {
                     match line {
                         "matching" => self.matching = true,
                         "text" => self.text = true,
+                        "ignore_punctuation" => self.ignore_punctuation = true,
+                        "ignore_articles" => self.ignore_articles = true,
                         "" => break,
                         _ => inner_errors.push(ParseRecallTypeError::UnknownSetting {
                             name: line.to_owned(),
@@ -101,10 +210,38 @@ impl FromStr for Set {
                         Some(("D", definition)) => {
                             card[Side::Definition].push_display(trim(definition).to_owned())
                         }
+                        // Empty accepted values are skipped so blank answers are only
+                        // accepted when asked for with `blank:`
+                        Some(("t", term)) if trim(term).is_empty() => {}
+                        Some(("d", definition)) if trim(definition).is_empty() => {}
                         Some(("t", term)) => card[Side::Term].push_accepted(trim(term).to_owned()),
+                        Some(("diff", difficulty)) => match trim(difficulty) {
+                            "easy" => card.difficulty = Difficulty::Easy,
+                            "hard" => card.difficulty = Difficulty::Hard,
+                            difficulty => errors.push(ParseFlashcardItemError::UnknownDifficulty {
+                                name: difficulty.to_owned(),
+                                line_number,
+                            }),
+                        },
                         Some(("d", definition)) => {
                             card[Side::Definition].push_accepted(trim(definition).to_owned())
                         }
+                        Some(("blank", side)) => match trim(side) {
+                            "term" => card[Side::Term].push_accepted(String::new()),
+                            "definition" => card[Side::Definition].push_accepted(String::new()),
+                            side => errors.push(ParseFlashcardItemError::UnknownSide {
+                                name: side.to_owned(),
+                                line_number,
+                            }),
+                        },
+                        Some(("wrap", wrap)) => match trim(wrap) {
+                            "yes" => card.wrap = true,
+                            "no" => card.wrap = false,
+                            value => errors.push(ParseFlashcardItemError::InvalidWrap {
+                                value: value.to_owned(),
+                                line_number,
+                            }),
+                        },
                         Some((tag, _)) => errors.push(ParseFlashcardItemError::UnknownTag {
                             tag: tag.to_owned(),
                             line_number,
@@ -136,8 +273,12 @@ impl FromStr for Set {
             }
         }
 
+        let mut metadata = Metadata::default();
+        let mut warnings = Vec::new();
         let mut recall_t = RecallSettings::default();
         let mut recall_d = RecallSettings::default();
+        let mut flashcards = FlashcardsSettings::default();
+        let mut rtl = RtlSettings::default();
         let mut cards = Vec::new();
 
         let mut errors = Vec::new();
@@ -147,13 +288,23 @@ impl FromStr for Set {
             if line.is_empty() {
                 continue;
             } else if line.starts_with('[') {
-                match line {
-                    "[recall_t]" => {
+                // Block names are matched ignoring case and whitespace inside the brackets
+                let name = line
+                    .strip_prefix('[')
+                    .and_then(|line| line.strip_suffix(']'))
+                    .map(|name| name.trim().to_ascii_lowercase());
+                match name.as_deref() {
+                    Some("meta") => metadata.update_from_lines(&mut lines, &mut warnings),
+                    Some("recall_t") => {
                         recall_t.update_from_lines(line_number, &mut lines, &mut errors)
                     }
-                    "[recall_d]" => {
+                    Some("recall_d") => {
                         recall_d.update_from_lines(line_number, &mut lines, &mut errors)
                     }
+                    Some("flashcards") => {
+                        flashcards.update_from_lines(line_number, &mut lines, &mut errors)
+                    }
+                    Some("rtl") => rtl.update_from_lines(line_number, &mut lines, &mut errors),
                     _ => {
                         errors.push(ParseBlockError::UnknownBlock {
                             name: line.to_owned(),
@@ -183,9 +334,13 @@ impl FromStr for Set {
 
         if errors.is_empty() {
             Ok(Set {
+                metadata,
                 recall_t,
                 recall_d,
+                flashcards,
+                rtl,
                 cards,
+                warnings,
             })
         } else {
             Err(errors)
@@ -193,6 +348,57 @@ impl FromStr for Set {
     }
 }
 
+impl Display for Set {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.metadata.is_used() {
+            writeln!(f, "[meta]")?;
+            for (key, value) in self.metadata.entries() {
+                if let Some(value) = value {
+                    writeln!(f, "{key}: {value}")?;
+                }
+            }
+            writeln!(f)?;
+        }
+        for (name, recall) in [("recall_t", self.recall_t), ("recall_d", self.recall_d)] {
+            if recall.is_used() {
+                writeln!(f, "[{name}]")?;
+                if recall.matching {
+                    writeln!(f, "matching")?;
+                }
+                if recall.text {
+                    writeln!(f, "text")?;
+                }
+                if recall.ignore_punctuation {
+                    writeln!(f, "ignore_punctuation")?;
+                }
+                if recall.ignore_articles {
+                    writeln!(f, "ignore_articles")?;
+                }
+                writeln!(f)?;
+            }
+        }
+        if let Some(grid) = self.flashcards.grid {
+            writeln!(f, "[flashcards]")?;
+            writeln!(f, "grid {}x{}", grid.x, grid.y)?;
+            writeln!(f)?;
+        }
+        if self.rtl.is_used() {
+            writeln!(f, "[rtl]")?;
+            for side in [Side::Term, Side::Definition] {
+                if self.rtl.side(side) {
+                    writeln!(f, "{side}")?;
+                }
+            }
+            writeln!(f)?;
+        }
+        for card in &self.cards {
+            write!(f, "{card}")?;
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub enum ParseBlockError {
     UnknownBlock {
@@ -207,6 +413,14 @@ pub enum ParseBlockError {
         errors: Vec<ParseFlashcardItemError>,
         line_number: u32,
     },
+    ParseFlashcardsSettingErrors {
+        errors: Vec<ParseFlashcardsSettingError>,
+        line_number: u32,
+    },
+    ParseRtlSettingErrors {
+        errors: Vec<ParseRtlSettingError>,
+        line_number: u32,
+    },
 }
 
 impl Display for ParseBlockError {
@@ -234,6 +448,27 @@ impl Display for ParseBlockError {
                     writeln!(f, "  {error}")?;
                 }
             }
+            ParseFlashcardsSettingErrors {
+                errors,
+                line_number,
+            } => {
+                writeln!(
+                    f,
+                    "Unable to parse flashcards settings on line {line_number}:"
+                )?;
+                for error in errors {
+                    writeln!(f, "  {error}")?;
+                }
+            }
+            ParseRtlSettingErrors {
+                errors,
+                line_number,
+            } => {
+                writeln!(f, "Unable to parse rtl settings on line {line_number}:")?;
+                for error in errors {
+                    writeln!(f, "  {error}")?;
+                }
+            }
         };
         Ok(())
     }
@@ -255,10 +490,50 @@ impl Display for ParseRecallTypeError {
     }
 }
 
+#[derive(Debug)]
+pub enum ParseFlashcardsSettingError {
+    UnknownSetting { name: String, line_number: u32 },
+    InvalidGrid { error: String, line_number: u32 },
+}
+
+impl Display for ParseFlashcardsSettingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use ParseFlashcardsSettingError::*;
+        match self {
+            UnknownSetting { name, line_number } => {
+                write!(f, "Unknown setting {name:?} on line {line_number}")
+            }
+            InvalidGrid { error, line_number } => {
+                write!(f, "Invalid grid size on line {line_number}: {error}")
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseRtlSettingError {
+    UnknownSide { name: String, line_number: u32 },
+}
+
+impl Display for ParseRtlSettingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use ParseRtlSettingError::*;
+        match self {
+            UnknownSide { name, line_number } => write!(
+                f,
+                "Unknown side {name:?} on line {line_number}, expected \"term\" or \"definition\""
+            ),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum ParseFlashcardItemError {
     MissingTag { line_number: u32 },
     UnknownTag { tag: String, line_number: u32 },
+    UnknownDifficulty { name: String, line_number: u32 },
+    InvalidWrap { value: String, line_number: u32 },
+    UnknownSide { name: String, line_number: u32 },
     MissingSide(Side),
 }
 
@@ -270,6 +545,17 @@ impl Display for ParseFlashcardItemError {
             UnknownTag { tag, line_number } => {
                 write!(f, "Unknown tag {tag:?} on line {line_number}")
             }
+            UnknownDifficulty { name, line_number } => {
+                write!(f, "Unknown difficulty {name:?} on line {line_number}")
+            }
+            InvalidWrap { value, line_number } => write!(
+                f,
+                "Invalid wrap {value:?} on line {line_number}, expected \"yes\" or \"no\""
+            ),
+            UnknownSide { name, line_number } => write!(
+                f,
+                "Unknown side {name:?} on line {line_number}, expected \"term\" or \"definition\""
+            ),
             MissingSide(side) => write!(f, "Missing {side}"),
         }
     }
@@ -283,24 +569,284 @@ macro_rules! load_set {
             None => return,
         }
     };
+    ($path:expr, fetch: $fetch:expr) => {
+        match match $fetch {
+            true => Set::load_from_url(&$path.to_string_lossy()),
+            false => Set::load_from_file_path($path),
+        } {
+            Some(set) => set,
+            None => return,
+        }
+    };
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseMetadataWarning {
+    UnknownKey { key: String, line_number: u32 },
+}
+
+impl Display for ParseMetadataWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use ParseMetadataWarning::*;
+        match self {
+            UnknownKey { key, line_number } => {
+                write!(f, "Unknown metadata key {key:?} on line {line_number}")
+            }
+        }
+    }
+}
+
+/// A likely mistake in a set, found by [`Set::problems`].  Cards are indexed from 0
+#[derive(Debug, Clone)]
+pub enum SetProblem {
+    /// Neither side has recall settings, so learn has nothing to ask
+    NoRecall,
+    ShortValue {
+        card: usize,
+        side: Side,
+        value: String,
+    },
+    DuplicateTerm {
+        card: usize,
+        first: usize,
+    },
+    /// A term is the same as a definition of the same card
+    SameSides {
+        card: usize,
+    },
+}
+
+impl Display for SetProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use SetProblem::*;
+        match self {
+            NoRecall => write!(f, "Set has no [recall_t] or [recall_d] block"),
+            ShortValue { card, side, value } => {
+                write!(f, "Card {} has a short {side}: {value:?}", card + 1)
+            }
+            DuplicateTerm { card, first } => write!(
+                f,
+                "Card {} has the same term as card {}",
+                card + 1,
+                first + 1
+            ),
+            SameSides { card } => {
+                write!(f, "Card {} has the same term and definition", card + 1)
+            }
+        }
+    }
+}
+
+/// Which sides of cards are written right to left, like Arabic or Hebrew, from a
+/// set's `[rtl]` block.  Text from these sides is aligned to the right of its box
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct RtlSettings {
+    pub term: bool,
+    pub definition: bool,
+}
+
+impl RtlSettings {
+    pub fn is_used(&self) -> bool {
+        self.term || self.definition
+    }
+
+    /// Returns true if `side` is written right to left
+    pub fn side(&self, side: Side) -> bool {
+        match side {
+            Side::Term => self.term,
+            Side::Definition => self.definition,
+        }
+    }
+
+    /// How to align text from `side`: to the right if it's written right to left,
+    /// otherwise `align`
+    pub fn align(&self, side: Side, align: TextAlignH) -> TextAlignH {
+        match self.side(side) {
+            true => TextAlignH::Right,
+            false => align,
+        }
+    }
+
+    fn update_from_lines<'a>(
+        &mut self,
+        line_number: u32,
+        lines: &mut impl Iterator<Item = (u32, &'a str)>,
+        errors: &mut Vec<ParseBlockError>,
+    ) {
+        let mut inner_errors = Vec::new();
+
+        for (line_number, line) in lines {
+            match line {
+                "term" => self.term = true,
+                "definition" => self.definition = true,
+                "" => break,
+                _ => inner_errors.push(ParseRtlSettingError::UnknownSide {
+                    name: line.to_owned(),
+                    line_number,
+                }),
+            }
+        }
+
+        if !inner_errors.is_empty() {
+            errors.push(ParseBlockError::ParseRtlSettingErrors {
+                errors: inner_errors,
+                line_number,
+            });
+        }
+    }
+}
+
+/// Information about a set, from its `[meta]` block
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub description: Option<String>,
 }
 
-#[derive(Debug, Default, Clone, Copy)]
+impl Metadata {
+    pub fn is_used(&self) -> bool {
+        self.entries().iter().any(|(_, value)| value.is_some())
+    }
+
+    /// The name of each field as written in a set, with its value
+    pub fn entries(&self) -> [(&'static str, Option<&str>); 3] {
+        [
+            ("title", self.title.as_deref()),
+            ("author", self.author.as_deref()),
+            ("description", self.description.as_deref()),
+        ]
+    }
+
+    fn update_from_lines<'a>(
+        &mut self,
+        lines: &mut impl Iterator<Item = (u32, &'a str)>,
+        warnings: &mut Vec<ParseMetadataWarning>,
+    ) {
+        for (line_number, line) in lines {
+            if line.is_empty() {
+                break;
+            }
+            let (key, value) = line.split_once(':').unwrap_or((line, ""));
+            let field = match key.trim() {
+                "title" => &mut self.title,
+                "author" => &mut self.author,
+                "description" => &mut self.description,
+                key => {
+                    warnings.push(ParseMetadataWarning::UnknownKey {
+                        key: key.to_owned(),
+                        line_number,
+                    });
+                    continue;
+                }
+            };
+            *field = Some(value.trim().to_owned());
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct RecallSettings {
     pub matching: bool,
     pub text: bool,
+    /// Compare typed answers without ASCII punctuation, so "its" is accepted for
+    /// "it's"
+    pub ignore_punctuation: bool,
+    /// Compare typed answers without a leading "a", "an", or "the", so "Nile" is
+    /// accepted for "the Nile"
+    pub ignore_articles: bool,
 }
 
 impl RecallSettings {
+    /// Articles removed from the start of answers by `ignore_articles`
+    const ARTICLES: [&'static str; 3] = ["a", "an", "the"];
+
     pub fn is_used(&self) -> bool {
         self.matching || self.text
     }
+
+    /// Returns `text` as it should be compared with these settings
+    fn normalize<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = text;
+        if self.ignore_articles {
+            if let Some((first, rest)) = text.split_once(char::is_whitespace) {
+                if Self::ARTICLES
+                    .iter()
+                    .any(|article| article.eq_ignore_ascii_case(first))
+                {
+                    text = rest.trim_start();
+                }
+            }
+        }
+        let is_ignored = |c: char| c.is_ascii_punctuation() && !matches!(c, '*' | '\\');
+        if self.ignore_punctuation && text.contains(is_ignored) {
+            Cow::Owned(text.replace(is_ignored, ""))
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+/// Settings for the flashcards study mode
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct FlashcardsSettings {
+    /// How many cards to show on each row and column if not given on the command line
+    pub grid: Option<Vec2<u16>>,
+}
+
+impl FlashcardsSettings {
+    fn update_from_lines<'a>(
+        &mut self,
+        line_number: u32,
+        lines: &mut impl Iterator<Item = (u32, &'a str)>,
+        errors: &mut Vec<ParseBlockError>,
+    ) {
+        let mut inner_errors = Vec::new();
+
+        for (line_number, line) in lines {
+            match line.split_once(' ') {
+                Some(("grid", size)) => match parse_size(size.trim()) {
+                    Ok(size) => self.grid = Some(size),
+                    Err(error) => inner_errors
+                        .push(ParseFlashcardsSettingError::InvalidGrid { error, line_number }),
+                },
+                _ if line.is_empty() => break,
+                _ => inner_errors.push(ParseFlashcardsSettingError::UnknownSetting {
+                    name: line.to_owned(),
+                    line_number,
+                }),
+            }
+        }
+
+        if !inner_errors.is_empty() {
+            errors.push(ParseBlockError::ParseFlashcardsSettingErrors {
+                errors: inner_errors,
+                line_number,
+            });
+        }
+    }
+}
+
+/// Parses a size like `4x3`, used for grids of cards
+pub fn parse_size(s: &str) -> Result<Vec2<u16>, String> {
+    let (x, y) = s.split_once('x').ok_or("expects inputs like \"1x1\"")?;
+    let x = x.parse::<u16>().map_err(|e| e.to_string())?;
+    let y = y.parse::<u16>().map_err(|e| e.to_string())?;
+    let v = Vec2 { x, y };
+    match v.into_iter().any(|x| x < 1) {
+        false => Ok(v),
+        true => Err("Size must be at least 1x1".to_owned()),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Flashcard {
     pub term: FlashcardText,
     pub definition: FlashcardText,
+    pub difficulty: Difficulty,
+    /// If text is wrapped when shown.  Cards of code or identifiers set this to
+    /// false to be shown verbatim and cut off instead
+    pub wrap: bool,
 }
 
 impl Flashcard {
@@ -308,6 +854,8 @@ impl Flashcard {
         Self {
             term: FlashcardText::empty(),
             definition: FlashcardText::empty(),
+            difficulty: Difficulty::Normal,
+            wrap: true,
         }
     }
 
@@ -319,6 +867,31 @@ impl Flashcard {
     }
 }
 
+impl Display for Flashcard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (side, display_tag, accepted_tag) in
+            [(Side::Term, 'T', 't'), (Side::Definition, 'D', 'd')]
+        {
+            for value in self[side].displayable() {
+                writeln!(f, "{display_tag}: {value}")?;
+            }
+            for value in self[side].other_accepted() {
+                match value.is_empty() {
+                    true => writeln!(f, "blank: {side}")?,
+                    false => writeln!(f, "{accepted_tag}: {value}")?,
+                }
+            }
+        }
+        if self.difficulty != Difficulty::Normal {
+            writeln!(f, "diff: {}", self.difficulty)?;
+        }
+        if !self.wrap {
+            writeln!(f, "wrap: no")?;
+        }
+        Ok(())
+    }
+}
+
 impl Index<Side> for Flashcard {
     type Output = FlashcardText;
 
@@ -339,7 +912,27 @@ impl IndexMut<Side> for Flashcard {
     }
 }
 
-#[derive(Clone)]
+/// How hard a card is, which changes how much it needs to be studied
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Difficulty::*;
+        match self {
+            Easy => write!(f, "easy"),
+            Normal => write!(f, "normal"),
+            Hard => write!(f, "hard"),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
 pub struct FlashcardText {
     values: SmallVec<[String; 1]>,
     num_display: usize,
@@ -374,9 +967,10 @@ impl FlashcardText {
 
     /// Returns true if this is valid
     ///
-    /// A flashcard text is valid if it has at least 1 value
+    /// A flashcard text is valid if it has at least 1 displayable value, since one is
+    /// needed whenever the card is shown
     fn is_valid(&self) -> bool {
-        !self.values.is_empty()
+        self.num_display > 0
     }
 
     pub fn push_display(&mut self, val: String) {
@@ -388,17 +982,105 @@ impl FlashcardText {
         self.values.push(val);
     }
 
+    /// Returns the displayable values in the order they're written in the set
     pub fn displayable(&self) -> &[String] {
         &self.values[..self.num_display]
     }
 
+    /// Returns the first displayable value written in the set, which is treated as
+    /// the main one wherever a single value is needed without choosing randomly
+    ///
+    /// Panics if this has no displayable values, which parsed sets never do
+    pub fn canonical(&self) -> &str {
+        &self.displayable()[0]
+    }
+
     pub fn display(&self) -> &str {
-        self.displayable().choose(&mut rand::thread_rng()).unwrap()
+        self.display_with(&mut rand::thread_rng())
+    }
+
+    /// Like [`display`](Self::display), but chooses using `rng`
+    pub fn display_with(&self, rng: &mut impl Rng) -> &str {
+        self.displayable().choose(rng).unwrap()
     }
 
     pub fn other_accepted(&self) -> &[String] {
         &self.values[self.num_display..]
     }
+
+    /// Returns the other accepted values as they'd be typed, with `\*` and `\\` shown
+    /// as `*` and `\`.  Blank answers and patterns using `*` are left out, since
+    /// neither can be shown as a single answer
+    pub fn readable_accepted(&self) -> Vec<String> {
+        self.other_accepted()
+            .iter()
+            .filter(|value| !value.is_empty())
+            .filter_map(|value| match wildcard_pieces(value).as_slice() {
+                [literal] => Some(literal.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns true if `text` is one of the displayable values of this, or matches
+    /// one of the accepted values.  Accepted values can use `*` to match any run of
+    /// characters, and `\*` for a literal `*`
+    ///
+    /// Both sides are compared as `settings` says, so with `ignore_punctuation` ASCII
+    /// punctuation other than `*` and `\` is removed, and with `ignore_articles` a
+    /// leading article is
+    pub fn contains(&self, text: &str, settings: RecallSettings) -> bool {
+        let text = settings.normalize(text);
+        self.displayable()
+            .iter()
+            .any(|v| settings.normalize(v) == text)
+            || self
+                .other_accepted()
+                .iter()
+                .any(|pattern| wildcard_matches(&settings.normalize(pattern), &text))
+    }
+}
+
+/// Splits `pattern` into the literal pieces between each `*`, with `\*` and `\\`
+/// unescaped to a literal `*` and `\`
+fn wildcard_pieces(pattern: &str) -> Vec<String> {
+    let mut pieces = vec![String::new()];
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if matches!(chars.peek(), Some('*' | '\\')) => {
+                pieces.last_mut().unwrap().push(chars.next().unwrap())
+            }
+            '*' => pieces.push(String::new()),
+            c => pieces.last_mut().unwrap().push(c),
+        }
+    }
+    pieces
+}
+
+/// Returns true if `text` matches `pattern`, where `*` matches any run of characters
+/// and `\*` and `\\` match a literal `*` and `\`
+fn wildcard_matches(pattern: &str, text: &str) -> bool {
+    match wildcard_pieces(pattern).as_slice() {
+        [literal] => literal == text,
+        [first, middle @ .., last] => {
+            let mut rest = match text
+                .strip_prefix(first.as_str())
+                .and_then(|rest| rest.strip_suffix(last.as_str()))
+            {
+                Some(rest) => rest,
+                None => return false,
+            };
+            for piece in middle {
+                match rest.find(piece.as_str()) {
+                    Some(index) => rest = &rest[index + piece.len()..],
+                    None => return false,
+                }
+            }
+            true
+        }
+        [] => unreachable!(),
+    }
 }
 
 impl From<String> for FlashcardText {
@@ -460,3 +1142,79 @@ impl Not for Side {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_headers_ignore_case_and_spaces() {
+        let set: Set = "[ Recall_T ]\nmatching\n\n[RECALL_D]   \ntext\n\nT: a\nD: b\n"
+            .parse()
+            .unwrap();
+        assert!(set.recall_t.matching && !set.recall_t.text);
+        assert!(set.recall_d.text && !set.recall_d.matching);
+        assert_eq!(set.cards.len(), 1);
+    }
+
+    /// Sets written the way [`Set`]'s `Display` impl writes them, so they should be
+    /// written back unchanged
+    const WRITTEN_SETS: [&str; 3] = [
+        "T: a\nD: b\n\n",
+        "[meta]\ntitle: Fruit\nauthor: Someone\n\n\
+        [recall_t]\nmatching\ntext\n\n\
+        [recall_d]\ntext\nignore_punctuation\nignore_articles\n\n\
+        [flashcards]\ngrid 3x2\n\n\
+        [rtl]\ndefinition\n\n\
+        T: apple\nT: Apple\nt: apples\nD: red fruit\nd: red: a fruit\n\n\
+        T: banana\nD: yellow fruit\nblank: definition\ndiff: hard\n\n\
+        T:  indented\nD: fn main() {}\ndiff: easy\nwrap: no\n\n",
+        "[recall_d]\nmatching\n\nT: x\nD: y\nd: z\n\nT: z\nD: w\n\n",
+    ];
+
+    #[test]
+    fn written_sets_round_trip() {
+        for text in WRITTEN_SETS {
+            let set: Set = text.parse().unwrap();
+            assert_eq!(set.to_string(), text);
+            assert_eq!(set.to_string().parse::<Set>().unwrap(), set);
+        }
+    }
+
+    #[test]
+    fn sets_round_trip_after_being_written() {
+        let text = "[RECALL_T]\nmatching\n\n[meta]\ndescription: Out of order\n\n\
+            T:apple\nd:\nD:   red fruit\nt: \n\nD: backwards\nT: card\nwrap: yes\n";
+        let set: Set = text.parse().unwrap();
+        let written = set.to_string();
+        assert_eq!(written.parse::<Set>().unwrap(), set);
+        assert_eq!(written.parse::<Set>().unwrap().to_string(), written);
+    }
+
+    #[test]
+    fn readable_accepted_skips_blanks_and_patterns() {
+        let set: Set = "T: a\nD: b\nd: colo*r\nd: 2 \\* 3\nblank: definition\nd: c:\\\\d\n"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            set.cards[0].definition.readable_accepted(),
+            ["2 * 3", "c:\\d"]
+        );
+    }
+
+    #[test]
+    fn unknown_block_headers_are_errors() {
+        for header in ["[recall t]", "[recall_t", "[]"] {
+            let errors = format!("{header}\nmatching\n\nT: a\nD: b\n")
+                .parse::<Set>()
+                .unwrap_err();
+            assert!(
+                matches!(
+                    errors[..],
+                    [ParseBlockError::UnknownBlock { line_number: 1, .. }]
+                ),
+                "{header}: {errors:?}"
+            );
+        }
+    }
+}
@@ -1,3 +1,5 @@
+pub mod events;
+
 #[macro_export]
 macro_rules! up {
     () => {
@@ -108,3 +110,29 @@ macro_rules! esc {
         })
     };
 }
+
+#[macro_export]
+macro_rules! known {
+    () => {
+        crossterm::event::Event::Key(crossterm::event::KeyEvent {
+            code: crossterm::event::KeyCode::Char('i'),
+            ..
+        }) | crossterm::event::Event::Key(crossterm::event::KeyEvent {
+            code: crossterm::event::KeyCode::Char('I'),
+            ..
+        })
+    };
+}
+
+#[macro_export]
+macro_rules! pause {
+    () => {
+        crossterm::event::Event::Key(crossterm::event::KeyEvent {
+            code: crossterm::event::KeyCode::Char('p'),
+            ..
+        }) | crossterm::event::Event::Key(crossterm::event::KeyEvent {
+            code: crossterm::event::KeyCode::Char('P'),
+            ..
+        })
+    };
+}
@@ -0,0 +1,209 @@
+use std::{
+    collections::VecDeque,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::Duration,
+};
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+
+use crate::output;
+
+/// Keys that are recorded by name
+const NAMED_KEYS: [(KeyCode, &str); 15] = [
+    (KeyCode::Backspace, "backspace"),
+    (KeyCode::Enter, "enter"),
+    (KeyCode::Left, "left"),
+    (KeyCode::Right, "right"),
+    (KeyCode::Up, "up"),
+    (KeyCode::Down, "down"),
+    (KeyCode::Home, "home"),
+    (KeyCode::End, "end"),
+    (KeyCode::PageUp, "pageup"),
+    (KeyCode::PageDown, "pagedown"),
+    (KeyCode::Tab, "tab"),
+    (KeyCode::BackTab, "backtab"),
+    (KeyCode::Delete, "delete"),
+    (KeyCode::Insert, "insert"),
+    (KeyCode::Esc, "esc"),
+];
+
+/// Reads events from the terminal, optionally recording them to a file, or replaying
+/// previously recorded events before reading from the terminal
+#[derive(Debug, Default)]
+pub struct Events {
+    record: Option<BufWriter<File>>,
+    /// Recorded events to return before reading from the terminal
+    replay: VecDeque<Event>,
+    /// Events read from the terminal but kept by
+    /// [`discard_queued_keys`](Self::discard_queued_keys), returned after the replay
+    kept: VecDeque<Event>,
+    /// Print every event to stderr as it's read
+    pub log: bool,
+}
+
+impl Events {
+    /// Opens the files to record to and replay from, printing error information if
+    /// either cannot be opened
+    pub fn open(record: Option<&Path>, replay: Option<&Path>) -> Option<Self> {
+        let mut events = Self::default();
+        if let Some(path) = replay {
+            let text = match fs::read_to_string(path) {
+                Ok(text) => text,
+                Err(err) => {
+                    output::write_fatal_error(&format!("Unable to open replay: {err}"));
+                    return None;
+                }
+            };
+            for (line_number, line) in (1..).zip(text.lines()) {
+                match parse_event(line) {
+                    Some(event) => events.replay.push_back(event),
+                    None => {
+                        output::write_fatal_error(&format!(
+                            "Invalid event {line:?} on line {line_number} of replay"
+                        ));
+                        return None;
+                    }
+                }
+            }
+        }
+        if let Some(path) = record {
+            match File::create(path) {
+                Ok(file) => events.record = Some(BufWriter::new(file)),
+                Err(err) => {
+                    output::write_fatal_error(&format!("Unable to create recording: {err}"));
+                    return None;
+                }
+            }
+        }
+        Some(events)
+    }
+
+    /// Returns the next replayed event, or reads one from the terminal once all of
+    /// them have been used, recording it if this is recording
+    ///
+    /// # Panics
+    ///
+    /// Panics if an event can't be read or recorded
+    pub fn read(&mut self) -> Event {
+        let event = self
+            .replay
+            .pop_front()
+            .or_else(|| self.kept.pop_front())
+            .unwrap_or_else(|| event::read().expect("Unable to read event"));
+        if self.log {
+            eprintln!("{event:?}");
+        }
+        if let Some(record) = &mut self.record {
+            write_event(record, &event)
+                .and_then(|()| record.flush())
+                .expect("Unable to record event");
+        }
+        event
+    }
+
+    /// Like [`read`](Self::read), but returns `None` if no event comes before
+    /// `timeout` passes.  Replayed and kept events come right away
+    ///
+    /// # Panics
+    ///
+    /// Panics if an event can't be read or recorded
+    pub fn read_timeout(&mut self, timeout: Duration) -> Option<Event> {
+        if self.replay.is_empty()
+            && self.kept.is_empty()
+            && !event::poll(timeout).expect("Unable to poll events")
+        {
+            return None;
+        }
+        Some(self.read())
+    }
+
+    /// Throws away keys that were pressed before now but haven't been read yet, so
+    /// keys mashed during one screen don't also act on the next.  Other events are
+    /// kept.  Does nothing while replaying
+    ///
+    /// # Panics
+    ///
+    /// Panics if an event can't be read
+    pub fn discard_queued_keys(&mut self) {
+        if !self.replay.is_empty() {
+            return;
+        }
+        while event::poll(Duration::ZERO).expect("Unable to poll events") {
+            match event::read().expect("Unable to read event") {
+                Event::Key(_) => {}
+                event => self.kept.push_back(event),
+            }
+        }
+    }
+}
+
+/// Writes `event` on its own line.  Events that can't be replayed (mouse and focus
+/// events, and unusual keys) are skipped
+fn write_event(out: &mut impl Write, event: &Event) -> io::Result<()> {
+    match event {
+        Event::Key(KeyEvent {
+            code, modifiers, ..
+        }) => {
+            let bits = modifiers.bits();
+            match code {
+                KeyCode::Char(c) => writeln!(out, "key {bits} char {c}"),
+                KeyCode::F(n) => writeln!(out, "key {bits} f{n}"),
+                code => match NAMED_KEYS.iter().find(|(key, _)| key == code) {
+                    Some((_, name)) => writeln!(out, "key {bits} {name}"),
+                    None => Ok(()),
+                },
+            }
+        }
+        Event::Resize(w, h) => writeln!(out, "resize {w} {h}"),
+        Event::Paste(text) => {
+            let text = text
+                .replace('\\', "\\\\")
+                .replace('\n', "\\n")
+                .replace('\r', "\\r");
+            writeln!(out, "paste {text}")
+        }
+        _ => Ok(()),
+    }
+}
+
+fn parse_event(line: &str) -> Option<Event> {
+    let (kind, rest) = line.split_once(' ')?;
+    match kind {
+        "key" => {
+            let (bits, name) = rest.split_once(' ')?;
+            let modifiers = KeyModifiers::from_bits(bits.parse().ok()?)?;
+            let code = if let Some(c) = name.strip_prefix("char ") {
+                let mut chars = c.chars();
+                let c = chars.next()?;
+                chars.next().is_none().then_some(KeyCode::Char(c))?
+            } else if let Some(Ok(n)) = name.strip_prefix('f').map(str::parse) {
+                KeyCode::F(n)
+            } else {
+                NAMED_KEYS.iter().find(|(_, n)| *n == name)?.0
+            };
+            Some(Event::Key(KeyEvent::new(code, modifiers)))
+        }
+        "resize" => {
+            let (w, h) = rest.split_once(' ')?;
+            Some(Event::Resize(w.parse().ok()?, h.parse().ok()?))
+        }
+        "paste" => {
+            let mut text = String::with_capacity(rest.len());
+            let mut chars = rest.chars();
+            while let Some(c) = chars.next() {
+                text.push(match c {
+                    '\\' => match chars.next()? {
+                        'n' => '\n',
+                        'r' => '\r',
+                        c => c,
+                    },
+                    c => c,
+                });
+            }
+            Some(Event::Paste(text))
+        }
+        _ => None,
+    }
+}
@@ -1,9 +1,11 @@
 use argh::FromArgs;
 
 mod debug;
+mod export;
 mod flashcards;
 mod input;
 mod output;
+mod split;
 mod study;
 mod vec2;
 
@@ -18,14 +20,20 @@ struct EasyFlashCards {
 #[argh(subcommand)]
 enum Subcommand {
     Debug(debug::Entry),
+    Export(export::Entry),
     Flashcards(study::flashcards::Entry),
     Learn(study::learn::Entry),
+    Pairs(study::pairs::Entry),
+    Split(split::Entry),
 }
 
 fn main() {
     match argh::from_env::<EasyFlashCards>().subcommand {
         Subcommand::Debug(cmd) => cmd.run(),
+        Subcommand::Export(cmd) => cmd.run(),
         Subcommand::Flashcards(cmd) => cmd.run(),
         Subcommand::Learn(cmd) => cmd.run(),
+        Subcommand::Pairs(cmd) => cmd.run(),
+        Subcommand::Split(cmd) => cmd.run(),
     }
 }
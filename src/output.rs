@@ -1,12 +1,20 @@
-use std::{fmt::Display, io};
+use std::{
+    fmt::Display,
+    io,
+    sync::atomic::{AtomicU8, Ordering},
+};
 
 use crossterm::{
-    cursor, execute, queue,
+    cursor, event, execute, queue,
     style::{self, Color, Stylize},
     terminal,
 };
 
+use crate::vec2::Vec2;
+
+pub mod select_list;
 pub mod text_box;
+pub mod timer;
 pub mod word_wrap;
 
 pub use text_box::*;
@@ -15,6 +23,27 @@ pub fn write_fatal_error(text: &str) {
     println!("{}", text.dark_red());
 }
 
+pub fn write_warning(text: &str) {
+    println!("{}", text.dark_yellow());
+}
+
+/// Returns the size of the terminal, or writes an error and returns `None` if it is
+/// smaller than `min_size`
+pub fn terminal_size_at_least(min_size: Vec2<u16>) -> Option<Vec2<u16>> {
+    let size: Vec2<u16> = terminal::size()
+        .expect("unable to get terminal size")
+        .into();
+    if size.x < min_size.x || size.y < min_size.y {
+        write_fatal_error(&format!(
+            "Terminal too small: must be at least {}x{}, but is {}x{}",
+            min_size.x, min_size.y, size.x, size.y
+        ));
+        None
+    } else {
+        Some(size)
+    }
+}
+
 pub fn len_base10(v: u16) -> u16 {
     ((v as f32).log10() + 1.0).floor() as u16
 }
@@ -31,56 +60,153 @@ impl Display for Repeat {
     }
 }
 
+/// The settings in use by the live [`TerminalSettings`], from
+/// [`TerminalSettings::bits`], so the signal handler only undoes those
+static ENABLED: AtomicU8 = AtomicU8::new(0);
+
 #[derive(Debug, Default)]
 pub struct TerminalSettings {
     alternate_screen: bool,
     cursor_hidden: bool,
     raw_mode: bool,
+    bracketed_paste: bool,
 }
 
 #[allow(dead_code)]
 impl TerminalSettings {
+    /// Also makes sure the terminal is restored if the program is killed by a signal
     pub fn new() -> Self {
+        restore_on_signal();
         Self::default()
     }
 
     pub fn enter_alternate_screen(&mut self) -> &mut Self {
         queue!(io::stdout(), terminal::EnterAlternateScreen).unwrap();
         self.alternate_screen = true;
+        self.publish();
         self
     }
 
     pub fn leave_alternate_screen(&mut self) -> &mut Self {
         queue!(io::stdout(), terminal::LeaveAlternateScreen).unwrap();
         self.alternate_screen = false;
+        self.publish();
         self
     }
 
     pub fn hide_cursor(&mut self) -> &mut Self {
         queue!(io::stdout(), cursor::Hide).unwrap();
         self.cursor_hidden = true;
+        self.publish();
         self
     }
 
     pub fn show_cursor(&mut self) -> &mut Self {
         queue!(io::stdout(), cursor::Show).unwrap();
         self.cursor_hidden = false;
+        self.publish();
+        self
+    }
+
+    /// Sets the title of the terminal window.  Terminals don't report their title, so
+    /// it can't be restored afterwards
+    pub fn set_title(&mut self, title: &str) -> &mut Self {
+        queue!(io::stdout(), terminal::SetTitle(title)).unwrap();
         self
     }
 
     pub fn enable_raw_mode(&mut self) -> &mut Self {
         terminal::enable_raw_mode().unwrap();
         self.raw_mode = true;
+        self.publish();
         self
     }
 
     pub fn disable_raw_mode(&mut self) -> &mut Self {
         terminal::disable_raw_mode().unwrap();
         self.raw_mode = false;
+        self.publish();
+        self
+    }
+
+    /// Makes pastes arrive as a single `Event::Paste` instead of a key event per char
+    pub fn enable_bracketed_paste(&mut self) -> &mut Self {
+        queue!(io::stdout(), event::EnableBracketedPaste).unwrap();
+        self.bracketed_paste = true;
+        self.publish();
+        self
+    }
+
+    /// Packs which settings are in use into the bits of a `u8`
+    fn bits(&self) -> u8 {
+        [
+            self.alternate_screen,
+            self.cursor_hidden,
+            self.raw_mode,
+            self.bracketed_paste,
+        ]
+        .into_iter()
+        .enumerate()
+        .fold(0, |bits, (index, enabled)| bits | (enabled as u8) << index)
+    }
+
+    /// Unpacks settings packed by [`bits`](Self::bits)
+    fn from_bits(bits: u8) -> Self {
+        let enabled = |index: u8| bits & 1 << index != 0;
+        Self {
+            alternate_screen: enabled(0),
+            cursor_hidden: enabled(1),
+            raw_mode: enabled(2),
+            bracketed_paste: enabled(3),
+        }
+    }
+
+    /// Records which settings are in use for the signal handler
+    fn publish(&self) {
+        ENABLED.store(self.bits(), Ordering::SeqCst);
+    }
+
+    pub fn disable_bracketed_paste(&mut self) -> &mut Self {
+        queue!(io::stdout(), event::DisableBracketedPaste).unwrap();
+        self.bracketed_paste = false;
+        self.publish();
         self
     }
 }
 
+/// Starts a thread that restores the terminal and exits when SIGINT, SIGTERM, or
+/// SIGHUP is received, so a killed session doesn't leave the terminal in raw mode.
+/// Only starts the thread the first time this is called
+#[cfg(unix)]
+fn restore_on_signal() {
+    use std::{process, sync::Once, thread};
+
+    use signal_hook::{
+        consts::{SIGHUP, SIGINT, SIGTERM},
+        iterator::Signals,
+    };
+
+    static START: Once = Once::new();
+    START.call_once(|| {
+        let mut signals = match Signals::new([SIGINT, SIGTERM, SIGHUP]) {
+            Ok(signals) => signals,
+            Err(_) => return,
+        };
+        thread::spawn(move || {
+            if let Some(signal) = signals.forever().next() {
+                // Holding stdout stops the main thread from writing in the middle of
+                // the restore, or after it before exiting
+                let _stdout = io::stdout().lock();
+                drop(TerminalSettings::from_bits(ENABLED.load(Ordering::SeqCst)));
+                process::exit(128 + signal);
+            }
+        });
+    });
+}
+
+#[cfg(not(unix))]
+fn restore_on_signal() {}
+
 impl Drop for TerminalSettings {
     fn drop(&mut self) {
         if self.alternate_screen {
@@ -92,10 +218,14 @@ impl Drop for TerminalSettings {
         if self.raw_mode {
             let _ = terminal::disable_raw_mode();
         }
+        if self.bracketed_paste {
+            let _ = execute!(io::stdout(), event::DisableBracketedPaste);
+        }
         let _ = execute!(
             io::stdout(),
             style::SetForegroundColor(Color::Reset),
             style::SetBackgroundColor(Color::Reset)
         );
+        ENABLED.store(0, Ordering::SeqCst);
     }
 }
@@ -0,0 +1,133 @@
+use std::io;
+
+use crossterm::{
+    cursor, queue,
+    style::{self, Attribute, Color},
+};
+
+use crate::{
+    output::{BoxOutline, Repeat, TextBox},
+    vec2::Vec2,
+};
+
+/// A scrollable list of items in an outlined box, one of which is highlighted
+#[derive(Debug, Clone)]
+pub struct SelectList<S: AsRef<str>> {
+    pub pos: Vec2<u16>,
+    pub size: Vec2<u16>,
+    pub outline: BoxOutline,
+    pub color: Color,
+    items: Vec<S>,
+    selected: usize,
+    /// The index of the first visible item
+    scroll: usize,
+}
+
+#[allow(dead_code)]
+impl<S: AsRef<str>> SelectList<S> {
+    pub fn new(items: Vec<S>) -> Self {
+        Self {
+            pos: Vec2::splat(0),
+            size: Vec2::new(5, 3),
+            outline: BoxOutline::LIGHT,
+            color: Color::White,
+            items,
+            selected: 0,
+            scroll: 0,
+        }
+    }
+
+    /// Draws this on screen.  Does not flush stdout
+    ///
+    /// # Panics
+    ///
+    /// Panics if size is not at least 3x3
+    pub fn draw(&self) -> &Self {
+        assert!(self.size.x >= 3 && self.size.y >= 3);
+
+        TextBox::new()
+            .pos(self.pos)
+            .size(self.size)
+            .outline(Some(self.outline))
+            .color(self.color)
+            .draw_outline();
+
+        let inner_size = self.size - Vec2::splat(2);
+        for row in 0..inner_size.y {
+            let index = self.scroll + row as usize;
+            let line: String = self
+                .items
+                .get(index)
+                .map_or("", |item| item.as_ref())
+                .chars()
+                .take(inner_size.x as usize)
+                .collect();
+            let line_len = line.chars().count() as u16;
+            let attribute = match index == self.selected && index < self.items.len() {
+                true => Attribute::Reverse,
+                false => Attribute::NoReverse,
+            };
+            queue!(
+                io::stdout(),
+                cursor::MoveTo(self.pos.x + 1, self.pos.y + 1 + row),
+                style::SetForegroundColor(self.color),
+                style::SetAttribute(attribute),
+                style::Print(line),
+                style::Print(Repeat(' ', inner_size.x - line_len)),
+                style::SetAttribute(Attribute::NoReverse),
+            )
+            .unwrap();
+        }
+        self
+    }
+
+    /// Moves the selection based on `event`, redrawing this if it changed.  Returns
+    /// the index of the selected item if it was chosen.  Does not flush stdout
+    pub fn update(&mut self, event: &crossterm::event::Event) -> Option<usize> {
+        match event {
+            crate::up!() if self.selected > 0 => {
+                self.set_selected(self.selected - 1);
+                None
+            }
+            crate::down!() if self.selected + 1 < self.items.len() => {
+                self.set_selected(self.selected + 1);
+                None
+            }
+            crate::click!() if !self.items.is_empty() => Some(self.selected),
+            _ => None,
+        }
+    }
+
+    /// Selects the item at `index`, scrolling to it if needed, and redraws this
+    pub fn set_selected(&mut self, index: usize) -> &mut Self {
+        let visible_rows = self.size.y.saturating_sub(2).max(1) as usize;
+        self.selected = index.min(self.items.len().saturating_sub(1));
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + visible_rows {
+            self.scroll = self.selected + 1 - visible_rows;
+        }
+        self.draw();
+        self
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn items(&self) -> &[S] {
+        &self.items
+    }
+
+    builder_impl::field!(pub pos(pos: Vec2<u16>));
+    builder_impl::field!(pub x(pos.x: u16));
+    builder_impl::field!(pub y(pos.y: u16));
+
+    builder_impl::field!(pub size(size: Vec2<u16>));
+    builder_impl::field!(pub width(size.x: u16));
+    builder_impl::field!(pub height(size.y: u16));
+
+    builder_impl::field!(pub outline(outline: BoxOutline));
+
+    builder_impl::field!(pub color(color: Color));
+}
@@ -1,12 +1,17 @@
-use std::{borrow::Cow, io};
+use std::{borrow::Cow, cell::Cell, io, iter};
 
 use crossterm::{
     cursor, queue,
     style::{self, Attribute, Attributes, Color},
 };
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 use crate::{
-    output::{word_wrap::WordWrap, Repeat},
+    output::{
+        word_wrap::{self, WordWrap},
+        Repeat,
+    },
     vec2::Vec2,
 };
 
@@ -20,6 +25,13 @@ pub struct TextBox {
     pub outline_color: Color,
     pub content_color: Color,
     pub attributes: Attributes,
+    /// If text is wrapped onto multiple lines.  If not, it is shown on a single line
+    /// and cut off at the edge of this
+    pub wrap: bool,
+    /// Put at the end of the last line when text doesn't fit
+    pub ellipsis: &'static str,
+    /// If some of the text last drawn was cut off
+    truncated: Cell<bool>,
 }
 
 #[allow(dead_code)]
@@ -73,6 +85,7 @@ impl TextBox {
                 style::Print(outline.br)
             )
             .unwrap();
+            self.reset_attributes();
         }
         self
     }
@@ -84,71 +97,79 @@ impl TextBox {
     /// Panics if size is not at least 5x3 (outlined) or at least 3x1 (no outline)
     pub fn draw_text(&self, text: &str) -> &Self {
         let lines_iter = self.get_lines_iter(text);
-        queue!(io::stdout(), style::SetForegroundColor(self.content_color)).unwrap();
+        queue!(
+            io::stdout(),
+            style::SetForegroundColor(self.content_color),
+            style::SetAttributes(self.attributes)
+        )
+        .unwrap();
 
         match self.text_align_h {
             TextAlignH::Left => self.draw_text_left_align(lines_iter),
             TextAlignH::Center => self.draw_text_center_align(lines_iter),
             TextAlignH::Right => self.draw_text_right_align(lines_iter),
         }
+        self.reset_attributes();
         self
     }
 
+    /// Undoes `self.attributes` so they don't affect anything drawn after this
+    fn reset_attributes(&self) {
+        if !self.attributes.is_empty() {
+            queue!(io::stdout(), style::SetAttribute(Attribute::Reset)).unwrap();
+        }
+    }
+
+    /// Returns the lines of `text` that fit in this, preceded by blank lines to align
+    /// them vertically, and records if any of `text` was cut off
     fn get_lines_iter<'a>(&self, text: &'a str) -> impl Iterator<Item = Cow<'a, str>> {
         let inner_size = self.inner_size();
-
-        enum LinesIter<'a> {
-            Top(WordWrap<'a>),
-            Other(std::vec::IntoIter<Cow<'a, str>>, usize),
-        }
-        impl<'a> Iterator for LinesIter<'a> {
-            type Item = Cow<'a, str>;
-
-            fn next(&mut self) -> Option<Self::Item> {
-                match self {
-                    LinesIter::Top(iter) => iter.next(),
-                    LinesIter::Other(iter, offset) => {
-                        if *offset > 0 {
-                            *offset -= 1;
-                            Some(Cow::Borrowed(""))
-                        } else {
-                            iter.next()
-                        }
+        let mut truncated = false;
+
+        let mut lines = match self.wrap {
+            true => {
+                let mut lines = self.word_wrap(text);
+                let mut vec = Vec::from_iter(lines.by_ref().take(inner_size.y as usize));
+                if lines.next().is_some() {
+                    truncated = true;
+                    if let (Some(line), false) =
+                        (vec.last_mut(), self.text_align_v == TextAlignV::Top)
+                    {
+                        let max_width =
+                            (inner_size.x as usize).saturating_sub(self.ellipsis.width());
+                        let len = fit_width(line, max_width);
+                        let line = line.to_mut();
+                        line.truncate(len);
+                        line.push_str(self.ellipsis);
                     }
                 }
+                vec
             }
-        }
-
-        match self.text_align_v {
-            TextAlignV::Top => LinesIter::Top(WordWrap::new(text, inner_size.x as usize)),
-            _ => {
-                let lines = {
-                    let mut lines = WordWrap::new(text, inner_size.x as usize);
-                    let mut vec = Vec::from_iter(lines.by_ref().take(inner_size.y as usize));
-                    if lines.next().is_some() {
-                        if let Some(line) = vec.last_mut() {
-                            let line = line.to_mut();
-                            let mut len = line.chars().count();
-                            while len > (inner_size.x - 3) as usize {
-                                line.pop();
-                                len -= 1;
-                            }
-                            line.push_str("...");
-                        }
-                    }
-                    vec
-                };
-                let len = lines.len();
-                LinesIter::Other(
-                    lines.into_iter(),
-                    match self.text_align_v {
-                        TextAlignV::Top => unreachable!(),
-                        TextAlignV::Center => (inner_size.y as usize).saturating_sub(len) / 2,
-                        TextAlignV::Bottom => (inner_size.y as usize).saturating_sub(len),
-                    },
-                )
+            false => vec![Cow::Borrowed(text)],
+        };
+        // Lines are wrapped by chars, so wide chars can still make them too wide to fit
+        for line in &mut lines {
+            let len = fit_width(line, inner_size.x as usize);
+            if len < line.len() {
+                truncated = true;
+                match line {
+                    Cow::Borrowed(text) => *text = &text[..len],
+                    Cow::Owned(text) => text.truncate(len),
+                }
             }
         }
+        self.truncated.set(truncated);
+
+        let offset = match self.text_align_v {
+            TextAlignV::Top => 0,
+            TextAlignV::Center => (inner_size.y as usize).saturating_sub(lines.len()) / 2,
+            TextAlignV::Bottom => (inner_size.y as usize).saturating_sub(lines.len()),
+        };
+        iter::repeat_n(Cow::Borrowed(""), offset).chain(lines)
+    }
+
+    fn word_wrap<'a>(&self, text: &'a str) -> WordWrap<'a> {
+        WordWrap::new(text, self.inner_size().x as usize)
     }
 
     fn draw_text_left_align<'a>(&self, lines: impl Iterator<Item = Cow<'a, str>>) {
@@ -190,7 +211,7 @@ impl TextBox {
                 queue!(
                     io::stdout(),
                     cursor::MoveTo(
-                        corner_pos.x + ((inner_size.x - line.chars().count() as u16) / 2),
+                        corner_pos.x + ((inner_size.x - line.width() as u16) / 2),
                         corner_pos.y + index as u16,
                     ),
                     style::Print(line),
@@ -216,7 +237,7 @@ impl TextBox {
                 queue!(
                     io::stdout(),
                     cursor::MoveTo(
-                        corner_pos.x - line.chars().count() as u16,
+                        corner_pos.x - line.width() as u16,
                         corner_pos.y + index as u16
                     ),
                     style::Print(line),
@@ -236,13 +257,19 @@ impl TextBox {
     pub fn overwrite_text(&self, old_text: &str, new_text: &str) -> &Self {
         let old_lines = self.get_lines_iter(old_text);
         let new_lines = self.get_lines_iter(new_text);
-        queue!(io::stdout(), style::SetForegroundColor(self.content_color)).unwrap();
+        queue!(
+            io::stdout(),
+            style::SetForegroundColor(self.content_color),
+            style::SetAttributes(self.attributes)
+        )
+        .unwrap();
 
         match self.text_align_h {
             TextAlignH::Left => self.overwrite_text_left_align(old_lines, new_lines),
             TextAlignH::Center => self.overwrite_text_center_align(old_lines, new_lines),
             TextAlignH::Right => self.overwrite_text_right_align(old_lines, new_lines),
         }
+        self.reset_attributes();
         self
     }
 
@@ -269,10 +296,10 @@ impl TextBox {
         )
         .unwrap();
         for old_line in old_lines {
-            let old_line_len = old_line.chars().count();
+            let old_line_len = old_line.width();
             if let Some(new_line) = new_lines.next().filter(|l| !l.is_empty()) {
                 let extra_len = old_line_len
-                    .checked_sub(new_line.chars().count())
+                    .checked_sub(new_line.width())
                     .unwrap_or_default();
                 queue!(
                     io::stdout(),
@@ -320,9 +347,9 @@ impl TextBox {
         let mut index = 0;
 
         for old_line in old_lines {
-            let old_line_len = old_line.chars().count();
+            let old_line_len = old_line.width();
             if let Some(new_line) = new_lines.next().filter(|l| !l.is_empty()) {
-                let new_line_len = new_line.chars().count();
+                let new_line_len = new_line.width();
                 if new_line_len >= old_line_len {
                     queue!(
                         io::stdout(),
@@ -373,7 +400,7 @@ impl TextBox {
                 queue!(
                     io::stdout(),
                     cursor::MoveTo(
-                        corner_pos.x + ((inner_size.x - line.chars().count() as u16) / 2),
+                        corner_pos.x + ((inner_size.x - line.width() as u16) / 2),
                         corner_pos.y + index as u16,
                     ),
                     style::Print(line),
@@ -404,9 +431,9 @@ impl TextBox {
         let mut index = 0;
 
         for old_line in old_lines {
-            let old_line_len = old_line.chars().count();
+            let old_line_len = old_line.width();
             if let Some(new_line) = new_lines.next().filter(|l| !l.is_empty()) {
-                let new_line_len = new_line.chars().count();
+                let new_line_len = new_line.width();
                 if new_line_len >= old_line_len {
                     queue!(
                         io::stdout(),
@@ -438,7 +465,7 @@ impl TextBox {
                 queue!(
                     io::stdout(),
                     cursor::MoveTo(
-                        corner_pos.x - line.chars().count() as u16,
+                        corner_pos.x - line.width() as u16,
                         corner_pos.y + index as u16
                     ),
                     style::Print(line),
@@ -449,6 +476,61 @@ impl TextBox {
         }
     }
 
+    /// Returns how many lines `text` takes up when wrapped to fit in this, ignoring
+    /// this's height
+    ///
+    /// # Panics
+    ///
+    /// Panics if size is not at least 4 wide (outlined) or at least 2 wide (no outline)
+    pub fn wrapped_line_count(&self, text: &str) -> usize {
+        match self.wrap {
+            true => word_wrap::line_count(text, self.inner_size().x as usize),
+            false => 1,
+        }
+    }
+
+    /// Returns if some of the text last drawn in this was cut off, so callers can
+    /// offer another way to see the rest
+    pub fn is_truncated(&self) -> bool {
+        self.truncated.get()
+    }
+
+    /// Sets the height of this so `text` fits without being cut off, but no taller
+    /// than `max_height`.  There's always room for a line of text, so this is at least
+    /// 3 tall if outlined and 1 tall if not, even if `max_height` is less
+    ///
+    /// # Panics
+    ///
+    /// Panics if size is not at least 4 wide (outlined) or at least 2 wide (no outline)
+    pub fn fit_height_to(&mut self, text: &str, max_height: u16) -> &mut Self {
+        let border = match self.outline {
+            Some(_) => 2,
+            None => 0,
+        };
+        let lines = self.wrapped_line_count(text).min(max_height as usize) as u16;
+        self.size.y = lines.saturating_add(border).min(max_height).max(border + 1);
+        self
+    }
+
+    /// Returns the smallest size, including the outline, of a default box that shows
+    /// all of `text` when no wider than `max_width`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_width` is less than 4
+    pub fn measure(text: &str, max_width: u16) -> Vec2<u16> {
+        let mut text_box = Self::new();
+        text_box.width(max_width);
+        let border = text_box.size - text_box.inner_size();
+        let inner_width = text_box.inner_size().x as usize;
+        let (width, height) = text_box
+            .word_wrap(text)
+            .fold((1, 0), |(width, height), line| {
+                (width.max(line.width().min(inner_width)), height + 1)
+            });
+        Vec2::new(width as u16, height.max(1)) + border
+    }
+
     pub fn inner_size(&self) -> Vec2<u16> {
         if self.outline.is_some() {
             self.size - Vec2::splat(2)
@@ -467,6 +549,9 @@ impl TextBox {
             outline_color: Color::White,
             content_color: Color::White,
             attributes: Attributes::default(),
+            wrap: true,
+            ellipsis: "...",
+            truncated: Cell::new(false),
         }
     }
 
@@ -504,6 +589,22 @@ impl TextBox {
         self.attributes.toggle(attribute);
         self
     }
+
+    builder_impl::field!(pub wrap(wrap: bool));
+    builder_impl::field!(pub ellipsis(ellipsis: &'static str));
+}
+
+/// Returns the length in bytes of the longest start of `text` no more than `width`
+/// columns wide, without splitting a grapheme cluster
+fn fit_width(text: &str, width: usize) -> usize {
+    let mut used = 0;
+    for (index, grapheme) in text.grapheme_indices(true) {
+        used += grapheme.width();
+        if used > width {
+            return index;
+        }
+    }
+    text.len()
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -549,6 +650,36 @@ impl BoxOutline {
         v: '║',
     };
 
+    pub const ROUNDED: Self = Self {
+        tl: '╭',
+        tr: '╮',
+        bl: '╰',
+        br: '╯',
+
+        h: '─',
+        v: '│',
+    };
+
+    /// For terminals and fonts without box drawing characters
+    pub const ASCII: Self = Self {
+        tl: '+',
+        tr: '+',
+        bl: '+',
+        br: '+',
+
+        h: '-',
+        v: '|',
+    };
+
+    /// Every visible style, in the order they're cycled through
+    pub const STYLES: [Self; 5] = [
+        Self::LIGHT,
+        Self::HEAVY,
+        Self::DOUBLE,
+        Self::ROUNDED,
+        Self::ASCII,
+    ];
+
     pub const ERASE: Self = Self {
         tl: ' ',
         tr: ' ',
@@ -571,6 +702,8 @@ pub struct MultiTextBox {
     pub outline_color: Color,
     pub content_color: Color,
     pub number: bool,
+    /// If text is wrapped onto multiple lines.  See [`TextBox::wrap`]
+    pub wrap: bool,
 }
 
 #[allow(dead_code)]
@@ -578,93 +711,88 @@ impl MultiTextBox {
     pub fn draw_outline(&self) -> &Self {
         let box_size = ((self.size - Vec2::splat(1)) / self.box_count) - Vec2::splat(1);
         let actual_size = (box_size + Vec2::splat(1)) * self.box_count + Vec2::splat(1);
-        let offset = (self.size - actual_size) / Vec2::splat(2);
-        let actual_pos = self.pos + offset;
-
-        // Top line
-        queue!(
-            io::stdout(),
-            actual_pos.move_to(),
-            style::SetForegroundColor(self.outline_color),
-            style::Print(self.outline.tl)
-        )
-        .unwrap();
-        for _ in 1..self.box_count.x {
-            queue!(
-                io::stdout(),
-                style::Print(Repeat(self.outline.h, box_size.x)),
-                style::Print(self.outline.lrb),
-            )
-            .unwrap();
-        }
-        queue!(
-            io::stdout(),
-            style::Print(Repeat(self.outline.h, box_size.x)),
-            style::Print(self.outline.tr),
-        )
-        .unwrap();
+        let offset = self.size.saturating_sub(actual_size) / Vec2::splat(2);
+        let actual_pos = self.pos.saturating_add(offset);
+        let outline = &self.outline;
 
-        // Middle lines
-        for _ in 0..(actual_size.y - 2) {
+        // Prints a horizontal line across all of the boxes
+        let print_line = |y: u16, (left, fill, join, right): (char, char, char, char)| {
             queue!(
                 io::stdout(),
-                cursor::MoveToColumn(actual_pos.x),
-                cursor::MoveDown(1),
-                style::Print(self.outline.v),
+                cursor::MoveTo(actual_pos.x, y),
+                style::Print(left)
             )
             .unwrap();
             for _ in 1..self.box_count.x {
                 queue!(
                     io::stdout(),
-                    cursor::MoveRight(box_size.x),
-                    style::Print(self.outline.inner_v),
+                    style::Print(Repeat(fill, box_size.x)),
+                    style::Print(join),
                 )
                 .unwrap();
             }
             queue!(
                 io::stdout(),
-                cursor::MoveRight(box_size.x),
-                style::Print(self.outline.v),
+                style::Print(Repeat(fill, box_size.x)),
+                style::Print(right),
             )
             .unwrap();
-        }
+        };
 
-        // Bottom line
-        queue!(
-            io::stdout(),
-            cursor::MoveToColumn(actual_pos.x),
-            cursor::MoveDown(1),
-            style::SetForegroundColor(self.outline_color),
-            style::Print(self.outline.bl)
-        )
-        .unwrap();
-        for _ in 1..self.box_count.x {
-            queue!(
-                io::stdout(),
-                style::Print(Repeat(self.outline.h, box_size.x)),
-                style::Print(self.outline.lrt),
-            )
-            .unwrap();
+        queue!(io::stdout(), style::SetForegroundColor(self.outline_color)).unwrap();
+        let mut y = actual_pos.y;
+        for row in 0..self.box_count.y {
+            // Top line, or the line between this row and the one above it
+            match row {
+                0 => print_line(y, (outline.tl, outline.h, outline.lrb, outline.tr)),
+                _ => print_line(
+                    y,
+                    (outline.tbr, outline.inner_h, outline.cross, outline.tbl),
+                ),
+            }
+            for _ in 0..box_size.y {
+                y += 1;
+                queue!(
+                    io::stdout(),
+                    cursor::MoveTo(actual_pos.x, y),
+                    style::Print(outline.v),
+                )
+                .unwrap();
+                for _ in 1..self.box_count.x {
+                    queue!(
+                        io::stdout(),
+                        cursor::MoveRight(box_size.x),
+                        style::Print(outline.inner_v),
+                    )
+                    .unwrap();
+                }
+                queue!(
+                    io::stdout(),
+                    cursor::MoveRight(box_size.x),
+                    style::Print(outline.v),
+                )
+                .unwrap();
+            }
+            y += 1;
         }
-        queue!(
-            io::stdout(),
-            style::Print(Repeat(self.outline.h, box_size.x)),
-            style::Print(self.outline.br),
-        )
-        .unwrap();
+        print_line(y, (outline.bl, outline.h, outline.lrt, outline.br));
 
         self
     }
 
     pub fn draw_text<'a>(&self, boxes: impl IntoIterator<Item = &'a str>) -> &Self {
-        if self.box_count.y != 1 {
-            unimplemented!("Vertical stacking multi text boxes not currently supported!");
-        }
+        self.draw_text_colored(boxes.into_iter().map(|text| (text, self.content_color)))
+    }
 
+    /// Draws the text of each box in its own color
+    pub fn draw_text_colored<'a>(
+        &self,
+        boxes: impl IntoIterator<Item = (&'a str, Color)>,
+    ) -> &Self {
         let box_size = ((self.size - Vec2::splat(1)) / self.box_count) - Vec2::splat(1);
         let actual_size = (box_size + Vec2::splat(1)) * self.box_count + Vec2::splat(1);
-        let offset = (self.size - actual_size) / Vec2::splat(2);
-        let actual_pos = self.pos + offset;
+        let offset = self.size.saturating_sub(actual_size) / Vec2::splat(2);
+        let actual_pos = self.pos.saturating_add(offset);
 
         let mut text_printer = TextBox {
             pos: actual_pos + Vec2::splat(1),
@@ -675,11 +803,18 @@ impl MultiTextBox {
             outline_color: Color::Black,
             content_color: self.content_color,
             attributes: Attributes::default(),
+            wrap: self.wrap,
+            ellipsis: "...",
+            truncated: Cell::new(false),
         };
 
-        for text in boxes {
-            text_printer.draw_text(text);
-            text_printer.pos.x += box_size.x + 1;
+        // Boxes are filled left to right, then top to bottom
+        let positions = Vec2::ZERO.positions_between(self.box_count);
+        for ((text, color), index) in boxes.into_iter().zip(positions) {
+            text_printer
+                .pos(actual_pos + Vec2::splat(1) + (box_size + Vec2::splat(1)) * index)
+                .content_color(color)
+                .draw_text(text);
         }
 
         self
@@ -696,6 +831,7 @@ impl MultiTextBox {
             outline_color: Color::White,
             content_color: Color::White,
             number: false,
+            wrap: true,
         }
     }
 
@@ -722,6 +858,7 @@ impl MultiTextBox {
         self
     }
     builder_impl::field!(pub number(number: bool));
+    builder_impl::field!(pub wrap(wrap: bool));
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -742,6 +879,8 @@ pub struct MultiBoxOutline {
 
     inner_h: char,
     inner_v: char,
+    /// Where inner lines cross, between four boxes
+    cross: char,
 }
 
 impl MultiBoxOutline {
@@ -761,7 +900,82 @@ impl MultiBoxOutline {
 
         inner_h: '─',
         inner_v: '│',
+        cross: '┼',
     };
+
+    pub const LIGHT: Self = Self {
+        tbr: '├',
+        tbl: '┤',
+        lrb: '┬',
+        lrt: '┴',
+
+        tl: '┌',
+        tr: '┐',
+        bl: '└',
+        br: '┘',
+
+        h: '─',
+        v: '│',
+
+        inner_h: '─',
+        inner_v: '│',
+        cross: '┼',
+    };
+
+    pub const HEAVY: Self = Self {
+        tbr: '┠',
+        tbl: '┨',
+        lrb: '┯',
+        lrt: '┷',
+
+        tl: '┏',
+        tr: '┓',
+        bl: '┗',
+        br: '┛',
+
+        h: '━',
+        v: '┃',
+
+        inner_h: '─',
+        inner_v: '│',
+        cross: '┼',
+    };
+
+    pub const ROUNDED: Self = Self {
+        tl: '╭',
+        tr: '╮',
+        bl: '╰',
+        br: '╯',
+        ..Self::LIGHT
+    };
+
+    pub const ASCII: Self = Self {
+        tbr: '+',
+        tbl: '+',
+        lrb: '+',
+        lrt: '+',
+
+        tl: '+',
+        tr: '+',
+        bl: '+',
+        br: '+',
+
+        h: '-',
+        v: '|',
+
+        inner_h: '-',
+        inner_v: '|',
+        cross: '+',
+    };
+
+    /// The same styles as [`BoxOutline::STYLES`], in the same order
+    pub const STYLES: [Self; 5] = [
+        Self::LIGHT,
+        Self::HEAVY,
+        Self::DOUBLE,
+        Self::ROUNDED,
+        Self::ASCII,
+    ];
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -0,0 +1,150 @@
+use std::time::{Duration, Instant};
+
+use crossterm::style::Color;
+
+use crate::{
+    output::{BoxOutline, TextBox},
+    vec2::Vec2,
+};
+
+/// Counts down from a length of time, and can draw the whole seconds left in a small
+/// outlined box
+#[derive(Debug, Clone)]
+pub struct Timer {
+    pub pos: Vec2<u16>,
+    pub outline: BoxOutline,
+    pub color: Color,
+    length: Duration,
+    end: Instant,
+    /// The seconds left when this was last drawn by [`tick`](Self::tick)
+    shown: Option<u64>,
+}
+
+#[allow(dead_code)]
+impl Timer {
+    /// Makes a timer that starts counting down `length` now
+    pub fn new(length: Duration) -> Self {
+        Self::new_at(length, Instant::now())
+    }
+
+    /// Makes a timer that started counting down `length` at `now`
+    fn new_at(length: Duration, now: Instant) -> Self {
+        Self {
+            pos: Vec2::splat(0),
+            outline: BoxOutline::LIGHT,
+            color: Color::White,
+            length,
+            end: now + length,
+            shown: None,
+        }
+    }
+
+    /// Starts counting down the whole length again
+    pub fn reset(&mut self) -> &mut Self {
+        self.reset_at(Instant::now())
+    }
+
+    fn reset_at(&mut self, now: Instant) -> &mut Self {
+        self.end = now + self.length;
+        self
+    }
+
+    /// Returns how long is left, or zero once this is done
+    pub fn remaining(&self) -> Duration {
+        self.remaining_at(Instant::now())
+    }
+
+    fn remaining_at(&self, now: Instant) -> Duration {
+        self.end.saturating_duration_since(now)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.is_done_at(Instant::now())
+    }
+
+    fn is_done_at(&self, now: Instant) -> bool {
+        self.remaining_at(now).is_zero()
+    }
+
+    /// Returns the seconds left, rounded up, so this shows 0 only once it's done
+    pub fn seconds(&self) -> u64 {
+        self.seconds_at(Instant::now())
+    }
+
+    fn seconds_at(&self, now: Instant) -> u64 {
+        let remaining = self.remaining_at(now);
+        remaining.as_secs() + (remaining.subsec_nanos() > 0) as u64
+    }
+
+    /// Returns the size of the box drawn by [`draw`](Self::draw)
+    pub fn size(&self) -> Vec2<u16> {
+        let digits = self.length.as_secs().max(1).to_string().len() as u16;
+        Vec2::new(digits + 2, 3)
+    }
+
+    /// Draws the seconds left if they've changed since this was last ticked, and
+    /// returns if this is done.  Does not flush stdout
+    pub fn tick(&mut self) -> bool {
+        let seconds = self.seconds();
+        if self.shown != Some(seconds) {
+            self.shown = Some(seconds);
+            self.draw();
+        }
+        seconds == 0
+    }
+
+    /// Draws the seconds left.  Does not flush stdout
+    pub fn draw(&self) -> &Self {
+        TextBox::new()
+            .pos(self.pos)
+            .size(self.size())
+            .outline(Some(self.outline))
+            .color(self.color)
+            .draw_outline_and_text(&self.seconds().to_string());
+        self
+    }
+
+    builder_impl::field!(pub pos(pos: Vec2<u16>));
+    builder_impl::field!(pub outline(outline: BoxOutline));
+    builder_impl::field!(pub color(color: Color));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn seconds_round_up() {
+        let start = Instant::now();
+        let seconds = |length, elapsed| Timer::new_at(length, start).seconds_at(start + elapsed);
+        assert_eq!(seconds(Duration::from_secs(10), Duration::ZERO), 10);
+        assert_eq!(seconds(Duration::from_secs(10), MS), 10);
+        assert_eq!(seconds(1500 * MS, Duration::ZERO), 2);
+        assert_eq!(seconds(2000 * MS, 1999 * MS), 1);
+        assert_eq!(seconds(Duration::ZERO, Duration::ZERO), 0);
+    }
+
+    #[test]
+    fn done_once_length_has_passed() {
+        let start = Instant::now();
+        assert!(Timer::new_at(Duration::ZERO, start).is_done_at(start));
+        let timer = Timer::new_at(200 * MS, start);
+        assert!(!timer.is_done_at(start + 199 * MS));
+        assert!(timer.is_done_at(start + 200 * MS));
+        assert_eq!(timer.seconds_at(start + 250 * MS), 0);
+    }
+
+    #[test]
+    fn reset_starts_over() {
+        let start = Instant::now();
+        let later = start + 250 * MS;
+        let mut timer = Timer::new_at(200 * MS, start);
+        assert!(timer.is_done_at(later));
+        timer.reset_at(later);
+        assert!(!timer.is_done_at(later));
+        assert_eq!(timer.remaining_at(later + 50 * MS), 150 * MS);
+        assert_eq!(timer.seconds_at(later), 1);
+    }
+}
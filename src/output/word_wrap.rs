@@ -20,11 +20,13 @@ impl<'a> Iterator for WordWrap<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         let mut len = 0;
+        // The length of the words so far in bytes, where `len` is in chars
+        let mut end = 0;
         for word in SplitKeepWhitespace::new(self.text) {
             let word_len = word.chars().count();
             if len + word_len > self.max_length {
                 return Some(if len > 0 {
-                    let (ret, new_text) = self.text.split_at(len);
+                    let (ret, new_text) = self.text.split_at(end);
                     self.text = new_text.trim_start();
                     ret.into()
                 } else {
@@ -39,6 +41,7 @@ impl<'a> Iterator for WordWrap<'a> {
                 });
             } else {
                 len += word_len;
+                end += word.len();
             }
         }
         self.text
@@ -50,6 +53,15 @@ impl<'a> Iterator for WordWrap<'a> {
 
 impl FusedIterator for WordWrap<'_> {}
 
+/// Returns how many lines `text` takes up when wrapped to `max_length`
+///
+/// # Panics
+///
+/// Panics if `max_length` is less than 2
+pub fn line_count(text: &str, max_length: usize) -> usize {
+    WordWrap::new(text, max_length).count()
+}
+
 pub struct SplitKeepWhitespace<'a> {
     text: &'a str,
 }
@@ -0,0 +1,44 @@
+use std::{fs, mem, num::NonZeroUsize, path::PathBuf};
+
+use argh::FromArgs;
+
+use crate::{flashcards::Set, load_set, output};
+
+/// Split a flashcard set into several smaller sets
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "split")]
+pub struct Entry {
+    /// the set to split
+    #[argh(positional)]
+    set: PathBuf,
+    /// how many cards to put in each part
+    #[argh(positional)]
+    size: NonZeroUsize,
+}
+
+impl Entry {
+    pub fn run(self) {
+        let mut set = load_set!(&self.set);
+        let stem = self.set.file_stem().unwrap_or_default().to_string_lossy();
+        let extension = self
+            .set
+            .extension()
+            .map(|e| format!(".{}", e.to_string_lossy()))
+            .unwrap_or_default();
+
+        let cards = mem::take(&mut set.cards);
+        for (index, cards) in cards.chunks(self.size.get()).enumerate() {
+            let part = Set {
+                cards: cards.to_vec(),
+                ..set.clone()
+            };
+            let path = self
+                .set
+                .with_file_name(format!("{stem}.part{}{extension}", index + 1));
+            if let Err(err) = fs::write(&path, part.to_string()) {
+                output::write_fatal_error(&format!("Unable to write {}: {err}", path.display()));
+                return;
+            }
+        }
+    }
+}
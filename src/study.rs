@@ -1,2 +1,98 @@
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+};
+
+use crossterm::{
+    event::Event,
+    queue,
+    terminal::{self, ClearType},
+};
+
+use crate::{
+    flashcards::{Set, SetProblem},
+    input::events::Events,
+    output::{self, TextBox},
+    vec2::Vec2,
+};
+
 pub mod flashcards;
 pub mod learn;
+pub mod pairs;
+
+/// Hides everything on screen behind a pause message until a key is pressed, then
+/// clears the screen and returns the size of the terminal.  The caller must redraw
+/// everything afterward
+pub fn pause(events: &mut Events) -> Vec2<u16> {
+    let mut term_size: Vec2<u16> = terminal::size()
+        .expect("unable to get terminal size")
+        .into();
+    loop {
+        queue!(io::stdout(), terminal::Clear(ClearType::All)).unwrap();
+        if term_size.x >= 3 && term_size.y >= 1 {
+            TextBox::new()
+                .outline(None)
+                .size(term_size)
+                .draw_text("Paused - press any key");
+        }
+        io::stdout().flush().unwrap();
+        match events.read() {
+            Event::Resize(w, h) => term_size = Vec2::new(w, h),
+            Event::Key(_) => break,
+            _ => {}
+        }
+    }
+    queue!(io::stdout(), terminal::Clear(ClearType::All)).unwrap();
+    term_size
+}
+
+/// If `term_size` is smaller than `min_size`, hides everything on screen behind a
+/// message asking for a bigger terminal until it's resized to fit.  Keys are ignored
+/// so they can't act on a screen that isn't shown.  Returns the size of the terminal,
+/// and if it was too small clears the screen, so the caller must redraw everything
+pub fn wait_for_size(
+    events: &mut Events,
+    mut term_size: Vec2<u16>,
+    min_size: Vec2<u16>,
+) -> Vec2<u16> {
+    if term_size.x >= min_size.x && term_size.y >= min_size.y {
+        return term_size;
+    }
+    while term_size.x < min_size.x || term_size.y < min_size.y {
+        queue!(io::stdout(), terminal::Clear(ClearType::All)).unwrap();
+        if term_size.x >= 3 && term_size.y >= 1 {
+            TextBox::new()
+                .outline(None)
+                .size(term_size)
+                .draw_text(&format!(
+                    "Terminal too small - make it at least {}x{}",
+                    min_size.x, min_size.y
+                ));
+        }
+        io::stdout().flush().unwrap();
+        if let Event::Resize(w, h) = events.read() {
+            term_size = Vec2::new(w, h);
+        }
+    }
+    queue!(io::stdout(), terminal::Clear(ClearType::All)).unwrap();
+    term_size
+}
+
+/// Prints the problems with `set` and its warnings, then exits with an error if
+/// there are any.  Used by `--strict`, which only reports empty values as too short.
+/// A set without recall settings is only a problem if `needs_recall` is set
+pub fn exit_if_problems(set: &Set, needs_recall: bool) {
+    let mut text = String::new();
+    for warning in &set.warnings {
+        writeln!(text, "{warning}").unwrap();
+    }
+    for problem in set.problems(1) {
+        if needs_recall || !matches!(problem, SetProblem::NoRecall) {
+            writeln!(text, "{problem}").unwrap();
+        }
+    }
+    if !text.is_empty() {
+        output::write_fatal_error(&text);
+        std::process::exit(1);
+    }
+}
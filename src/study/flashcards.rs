@@ -1,15 +1,22 @@
-use std::path::PathBuf;
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    time::Duration,
+};
 
 use argh::FromArgs;
 use crossterm::{
-    event::{self, Event},
-    terminal,
+    cursor,
+    event::{Event, KeyCode, KeyEvent, KeyModifiers},
+    queue, style,
+    terminal::{self, ClearType},
 };
 
 use crate::{
-    flashcards::{Set, Side},
+    flashcards::{parse_size, Flashcard, Set, Side},
+    input::events::Events,
     load_set,
-    output::TerminalSettings,
+    output::{self, timer::Timer, TerminalSettings},
     vec2::Vec2,
 };
 
@@ -22,38 +29,128 @@ pub struct Entry {
     /// the set to study
     #[argh(positional)]
     set: PathBuf,
-    /// how many flashcards to put on each row and column, defaults to 1x1
+    /// treat `set` as a url and download it (requires the "fetch" feature)
+    #[argh(switch)]
+    fetch: bool,
+    /// refuse to start if the set has warnings, like empty values or repeated terms
+    #[argh(switch)]
+    strict: bool,
+    /// show the set's title as the terminal window's title, which is left there after
+    /// exiting since terminals can't report their title
+    #[argh(switch)]
+    window_title: bool,
+    /// show the definition side of every card first
+    #[argh(switch)]
+    definition_first: bool,
+    /// flip the selected card every this many seconds, then go on to the next card
+    /// once both sides have been shown.  Any key pauses, and escape exits
+    #[argh(option, from_str_fn(parse_seconds))]
+    autoflip: Option<Duration>,
+    /// record every input to a file so the session can be replayed
+    #[argh(option)]
+    record: Option<PathBuf>,
+    /// replay inputs recorded with --record before reading from the terminal
+    #[argh(option)]
+    replay: Option<PathBuf>,
+    /// stay out of raw mode and the alternate screen, and log every input to stderr,
+    /// so errors and events can be seen while debugging
+    #[argh(switch)]
+    no_raw_on_error: bool,
+    /// how many flashcards to put on each row and column, defaults to the set's
+    /// grid setting or 1x1
     #[argh(positional, from_str_fn(parse_size))]
     card_count: Option<Vec2<u16>>,
 }
 
 impl Entry {
     pub fn run(self) {
-        let set = load_set!(&self.set);
+        let set = load_set!(&self.set, fetch: self.fetch);
+        if self.strict {
+            super::exit_if_problems(&set, false);
+        }
         let mut scroll_dst = 0u16;
 
-        let card_count = self.card_count.unwrap_or_else(|| Vec2::splat(1));
+        let card_count = self
+            .card_count
+            .or(set.flashcards.grid)
+            .unwrap_or_else(|| Vec2::splat(1));
         let cards = set.cards;
-        let mut sides = vec![Side::Term; cards.len()];
-        let term_size: Vec2<_> = terminal::size()
-            .expect("unable to get terminal size")
-            .into();
+        let first_side = match self.definition_first {
+            true => Side::Definition,
+            false => Side::Term,
+        };
+        let mut sides = vec![first_side; cards.len()];
+        let min_size = grid::MIN_CARD_SIZE.join(card_count, u16::saturating_mul);
+        let mut term_size = match output::terminal_size_at_least(min_size) {
+            Some(size) => size,
+            None => return,
+        };
+        let mut events = match Events::open(self.record.as_deref(), self.replay.as_deref()) {
+            Some(events) => events,
+            None => return,
+        };
+
+        events.log = self.no_raw_on_error;
 
         let mut term_settings = TerminalSettings::new();
-        term_settings
-            .enter_alternate_screen()
-            .hide_cursor()
-            .enable_raw_mode();
+        if !self.no_raw_on_error {
+            term_settings
+                .enter_alternate_screen()
+                .hide_cursor()
+                .enable_raw_mode();
+        }
+        if let (true, Some(title)) = (self.window_title, &set.metadata.title) {
+            term_settings.set_title(title);
+        }
 
-        let mut grid = grid::FlashcardGrid::new(card_count);
-        grid.fill_from_text(cards.iter().map(|card| card[Side::Term].display()))
+        let mut grid = grid::FlashcardGrid::new(card_count, set.rtl);
+        grid.fill_from_cards(cards.iter().map(|card| grid::face(card, first_side)))
             .size_to(term_size);
 
+        let mut autoflip_timer = self.autoflip.map(Timer::new);
+        // If the selected card has been flipped by `--autoflip`
+        let mut autoflipped = false;
         loop {
-            match event::read().expect("Unable to read event") {
+            let event = match &mut autoflip_timer {
+                Some(timer) => match events.read_timeout(timer.remaining()) {
+                    Some(event @ (crate::esc!() | Event::Resize(..))) => event,
+                    Some(Event::Key(_)) => {
+                        let size = super::pause(&mut events);
+                        term_size = super::wait_for_size(&mut events, size, min_size);
+                        grid.size_to(term_size);
+                        timer.reset();
+                        continue;
+                    }
+                    Some(event) => event,
+                    None => {
+                        match autoflipped {
+                            false => flip_selected(&mut grid, scroll_dst, &cards, &mut sides),
+                            true => select_next(&mut grid, &mut scroll_dst, &cards, &sides),
+                        }
+                        autoflipped = !autoflipped;
+                        timer.reset();
+                        continue;
+                    }
+                },
+                None => events.read(),
+            };
+            match event {
                 Event::Resize(x, y) => {
-                    grid.size_to(Vec2::new(x, y));
+                    term_size = super::wait_for_size(&mut events, Vec2::new(x, y), min_size);
+                    grid.size_to(term_size);
                 }
+                Event::Key(KeyEvent {
+                    code: KeyCode::Char('/'),
+                    ..
+                }) => search(
+                    &mut grid,
+                    &mut events,
+                    &mut term_size,
+                    min_size,
+                    &mut scroll_dst,
+                    &cards,
+                    &sides,
+                ),
                 crate::up!() => grid.update(|grid| {
                     if let Some(y) = grid.selected().y.checked_sub(1) {
                         grid.set_selected(Vec2::new(grid.selected().x, y));
@@ -63,7 +160,7 @@ impl Entry {
                             cards
                                 .iter()
                                 .zip(sides.iter())
-                                .map(|(card, side)| (card[*side].display(), *side))
+                                .map(|(card, side)| grid::face(card, *side))
                                 .skip((scroll_dst * grid.card_count().x) as usize),
                         );
                     }
@@ -82,7 +179,7 @@ impl Entry {
                                 cards
                                     .iter()
                                     .zip(sides.iter())
-                                    .map(|(card, side)| (card[*side].display(), *side))
+                                    .map(|(card, side)| grid::face(card, *side))
                                     .skip((scroll_dst * grid.card_count().x) as usize),
                             );
                         }
@@ -101,18 +198,29 @@ impl Entry {
                         grid.set_selected(new_selected);
                     }
                 }),
-                crate::click!() => {
+                crate::click!() => flip_selected(&mut grid, scroll_dst, &cards, &mut sides),
+                Event::Key(KeyEvent {
+                    code: KeyCode::Char('f' | 'F'),
+                    ..
+                }) => {
                     grid.update(|grid| {
-                        let mut selected = grid.selected();
-                        let width = grid.card_count().x as usize;
-                        let card = (&mut grid[selected]).as_mut().unwrap();
-                        let new_side = !card.1;
-                        selected.y += scroll_dst;
-                        let index = selected.index_row_major(width);
-                        sides[index] = new_side;
-                        *card = (cards[index][new_side].display(), new_side);
+                        let card_count = grid.card_count();
+                        for pos in Vec2::ZERO.positions_between(card_count) {
+                            if let Some(card) = &mut grid[pos] {
+                                let index = (pos + Vec2::new(0, scroll_dst))
+                                    .index_row_major(card_count.x as usize);
+                                let new_side = !card.side;
+                                sides[index] = new_side;
+                                *card = grid::face(&cards[index], new_side);
+                            }
+                        }
                     });
                 }
+                crate::pause!() => {
+                    let size = super::pause(&mut events);
+                    term_size = super::wait_for_size(&mut events, size, min_size);
+                    grid.size_to(term_size);
+                }
                 Event::Key(_) => break,
                 _ => {}
             }
@@ -122,13 +230,159 @@ impl Entry {
     }
 }
 
-fn parse_size(s: &str) -> Result<Vec2<u16>, String> {
-    let (x, y) = s.split_once('x').ok_or("expects inputs like \"1x1\"")?;
-    let x = x.parse::<u16>().map_err(|e| e.to_string())?;
-    let y = y.parse::<u16>().map_err(|e| e.to_string())?;
-    let v = Vec2 { x, y };
-    match v.into_iter().any(|x| x < 1) {
-        false => Ok(v),
-        true => Err("Size must be at least 1x1".to_owned()),
+/// Shows the other side of the selected card
+fn flip_selected<'a>(
+    grid: &mut grid::FlashcardGrid<'a>,
+    scroll_dst: u16,
+    cards: &'a [Flashcard],
+    sides: &mut [Side],
+) {
+    grid.update(|grid| {
+        let mut selected = grid.selected();
+        let width = grid.card_count().x as usize;
+        let card = (&mut grid[selected]).as_mut().unwrap();
+        let new_side = !card.side;
+        selected.y += scroll_dst;
+        let index = selected.index_row_major(width);
+        sides[index] = new_side;
+        *card = grid::face(&cards[index], new_side);
+    });
+}
+
+/// Selects the card after the selected one, or the first card after the last one,
+/// scrolling as little as possible to show it
+fn select_next<'a>(
+    grid: &mut grid::FlashcardGrid<'a>,
+    scroll_dst: &mut u16,
+    cards: &'a [Flashcard],
+    sides: &[Side],
+) {
+    grid.update(|grid| {
+        let card_count = grid.card_count();
+        let width = card_count.x as usize;
+        let index = (grid.selected() + Vec2::new(0, *scroll_dst)).index_row_major(width) + 1;
+        let index = if index < cards.len() { index } else { 0 };
+        let row = (index / width) as u16;
+        let scroll = (*scroll_dst)
+            .min(row)
+            .max((row + 1).saturating_sub(card_count.y));
+        if scroll != *scroll_dst {
+            *scroll_dst = scroll;
+            grid.fill_from_cards(
+                cards
+                    .iter()
+                    .zip(sides.iter())
+                    .map(|(card, side)| grid::face(card, *side))
+                    .skip(scroll as usize * width),
+            );
+        }
+        grid.set_selected(Vec2::new((index % width) as u16, row - scroll));
+    });
+}
+
+/// Reads a search query, typed on the bottom line, and selects the first card whose
+/// term or definition contains it, ignoring case.  Enter keeps the selection and
+/// escape goes back to the card selected before searching
+fn search<'a>(
+    grid: &mut grid::FlashcardGrid<'a>,
+    events: &mut Events,
+    term_size: &mut Vec2<u16>,
+    min_size: Vec2<u16>,
+    scroll_dst: &mut u16,
+    cards: &'a [Flashcard],
+    sides: &[Side],
+) {
+    let start_scroll = *scroll_dst;
+    let mut start_selected = Vec2::ZERO;
+    grid.update(|grid| start_selected = grid.selected());
+
+    // Scrolls so the card at `index` can be seen and selects it
+    let mut select = |grid: &mut grid::FlashcardGrid<'a>, index: Option<usize>| {
+        grid.update(|grid| {
+            let width = grid.card_count().x as usize;
+            let (scroll, selected) = match index {
+                Some(index) => {
+                    let row = (index / width) as u16;
+                    let scroll = match row.checked_sub(*scroll_dst) {
+                        Some(y) if y < grid.card_count().y => *scroll_dst,
+                        _ => row,
+                    };
+                    (scroll, Vec2::new((index % width) as u16, row - scroll))
+                }
+                None => (start_scroll, start_selected),
+            };
+            if scroll != *scroll_dst {
+                *scroll_dst = scroll;
+                grid.fill_from_cards(
+                    cards
+                        .iter()
+                        .zip(sides.iter())
+                        .map(|(card, side)| grid::face(card, *side))
+                        .skip(scroll as usize * width),
+                );
+            }
+            grid.set_selected(selected);
+        });
+    };
+
+    let mut query = String::new();
+    loop {
+        queue!(
+            io::stdout(),
+            cursor::MoveTo(0, term_size.y - 1),
+            terminal::Clear(ClearType::CurrentLine),
+            style::Print(format!("/{query}")),
+        )
+        .unwrap();
+        io::stdout().flush().unwrap();
+        match events.read() {
+            Event::Key(KeyEvent {
+                code: KeyCode::Enter,
+                ..
+            }) => break,
+            crate::esc!() => {
+                select(grid, None);
+                break;
+            }
+            Event::Key(KeyEvent {
+                code: KeyCode::Backspace,
+                ..
+            }) => {
+                query.pop();
+            }
+            // Shortcuts like ctrl+c aren't text
+            Event::Key(KeyEvent {
+                code: KeyCode::Char(c),
+                modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
+                ..
+            }) => query.push(c),
+            Event::Resize(x, y) => {
+                *term_size = super::wait_for_size(events, Vec2::new(x, y), min_size);
+                grid.size_to(*term_size);
+                continue;
+            }
+            _ => continue,
+        }
+        let query = query.to_lowercase();
+        let found = cards.iter().position(|card| {
+            [Side::Term, Side::Definition].into_iter().any(|side| {
+                card[side]
+                    .displayable()
+                    .iter()
+                    .any(|value| value.to_lowercase().contains(&query))
+            })
+        });
+        if !query.is_empty() {
+            select(grid, found);
+        }
+    }
+    // Redraw everything to clear the search line
+    grid.size_to(*term_size);
+}
+
+fn parse_seconds(s: &str) -> Result<Duration, String> {
+    match s.parse::<f32>().map_err(|e| e.to_string())? {
+        seconds if seconds > 0.0 && seconds.is_finite() => Ok(Duration::from_secs_f32(seconds)),
+        _ => Err("Seconds must be more than 0".to_owned()),
     }
 }
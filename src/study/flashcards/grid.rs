@@ -3,12 +3,17 @@ use std::{
     ops::{Index, IndexMut},
 };
 
+use crossterm::{cursor, queue, style, style::Color};
+
 use crate::{
-    flashcards::Side,
-    output::{BoxOutline, TextBox},
+    flashcards::{Flashcard, RtlSettings, Side},
+    output::{BoxOutline, TextAlignH, TextBox},
     vec2::Vec2,
 };
 
+/// The smallest size a card can be drawn at
+pub const MIN_CARD_SIZE: Vec2<u16> = Vec2::new(5, 3);
+
 #[derive(Debug)]
 pub struct FlashcardGrid<'a> {
     card_count: Vec2<u16>,
@@ -17,34 +22,51 @@ pub struct FlashcardGrid<'a> {
     selected: Vec2<u16>,
     /// The cards that can currently be seen.
     /// The length of this is equal to `self.card_count.area()`
-    cards: Vec<Option<(&'a str, Side)>>,
+    cards: Vec<Option<Face<'a>>>,
+    /// Which sides are aligned to the right because they're written right to left
+    rtl: RtlSettings,
+}
+
+/// What's shown on a card in the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Face<'a> {
+    pub text: &'a str,
+    /// The side `text` is from
+    pub side: Side,
+    /// How many other answers are accepted for `side`, not counting blank answers or
+    /// patterns
+    pub accepted: usize,
+    /// If `text` is wrapped
+    pub wrap: bool,
+}
+
+/// Returns the face to show for `side` of `card`
+pub fn face(card: &Flashcard, side: Side) -> Face<'_> {
+    Face {
+        text: card[side].display(),
+        side,
+        accepted: card[side].readable_accepted().len(),
+        wrap: card.wrap,
+    }
 }
 
+/// Superscript digits for the badge showing how many other answers are accepted
+const BADGE_DIGITS: [char; 9] = ['¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+
 impl<'a> FlashcardGrid<'a> {
     #[must_use]
-    pub fn new(card_count: Vec2<u16>) -> Self {
+    pub fn new(card_count: Vec2<u16>, rtl: RtlSettings) -> Self {
         FlashcardGrid {
             card_count,
-            card_size: Vec2::new(5, 3),
+            card_size: MIN_CARD_SIZE,
             offset: Vec2::ZERO,
             selected: Vec2::ZERO,
             cards: vec![None; card_count.area() as usize],
+            rtl,
         }
     }
 
-    pub fn fill_from_text(&mut self, cards_iter: impl Iterator<Item = &'a str>) -> &mut Self {
-        cards_iter
-            .take(self.card_count.area() as usize)
-            .map(|text| Some((text, Side::Term)))
-            .enumerate()
-            .for_each(|(index, value)| self.cards[index] = value);
-        self
-    }
-
-    pub fn fill_from_cards(
-        &mut self,
-        cards_iter: impl Iterator<Item = (&'a str, Side)>,
-    ) -> &mut Self {
+    pub fn fill_from_cards(&mut self, cards_iter: impl Iterator<Item = Face<'a>>) -> &mut Self {
         self.cards.clear();
         let area = self.card_count.area() as usize;
         self.cards.extend(cards_iter.take(area).map(Some));
@@ -55,21 +77,24 @@ impl<'a> FlashcardGrid<'a> {
     #[must_use]
     fn card_printer(&self) -> TextBox {
         let mut card_printer = TextBox::new();
-        card_printer.text_align_h = crate::output::TextAlignH::Center;
+        card_printer.text_align_h = TextAlignH::Center;
         card_printer.text_align_v = crate::output::TextAlignV::Center;
         card_printer.size = self.card_size;
+        // Cards can be small, so save space with a single character
+        card_printer.ellipsis = "…";
         card_printer
     }
 
     /// Resizes and prints this
     pub fn size_to(&mut self, term_size: Vec2<u16>) -> &mut Self {
-        let card_size = Some(term_size / self.card_count).filter(|s| s.x >= 5 && s.y >= 3);
+        let card_size = Some(term_size / self.card_count)
+            .filter(|s| s.x >= MIN_CARD_SIZE.x && s.y >= MIN_CARD_SIZE.y);
         if let Some(card_size) = card_size {
             self.card_size = card_size;
-            self.offset = (term_size - (self.card_count * card_size)) / Vec2::splat(2);
+            self.offset = term_size.saturating_sub(self.card_count * card_size) / Vec2::splat(2);
             self.print();
         } else {
-            self.card_size = Vec2::new(5, 3);
+            self.card_size = MIN_CARD_SIZE;
             self.offset = Vec2::ZERO;
         }
         self
@@ -81,17 +106,20 @@ impl<'a> FlashcardGrid<'a> {
 
     fn print_card<'b>(&self, pos: Vec2<u16>, printer: &'b mut TextBox) -> &'b mut TextBox {
         let index = pos.index_row_major(self.card_count.x as usize);
-        if let Some((text, side)) = self.cards[index] {
+        if let Some(face) = self.cards[index] {
             self.print_at(pos, printer)
                 .outline(outline_type(pos == self.selected))
-                .color(side.color())
-                .draw_outline_and_text(text);
+                .color(face.side.color())
+                .text_align_h(self.rtl.align(face.side, TextAlignH::Center))
+                .wrap(face.wrap)
+                .draw_outline_and_text(face.text);
+            draw_badge(printer, face.accepted);
         }
         printer
     }
 
     pub fn print(&self) -> &Self {
-        use crossterm::{queue, terminal};
+        use crossterm::terminal;
         queue!(io::stdout(), terminal::Clear(terminal::ClearType::All)).unwrap();
         let mut printer = self.card_printer();
         for pos in Vec2::ZERO.positions_between(self.card_count) {
@@ -111,28 +139,49 @@ impl<'a> FlashcardGrid<'a> {
         for pos in Vec2::ZERO.positions_between(self.card_count) {
             let index = pos.index_row_major(self.card_count.x as usize);
             match (old_cards[index], self.cards[index]) {
-                (Some((old_text, old_side)), Some((text, side))) => {
-                    let color_changed = old_side != side;
-                    let redraw_outline =
-                        ((pos == old_selected) != (pos == self.selected)) || color_changed;
-                    let redraw_text = old_text != text || color_changed;
+                (Some(old), Some(new)) => {
+                    let color_changed = old.side != new.side;
+                    let redraw_outline = ((pos == old_selected) != (pos == self.selected))
+                        || color_changed
+                        || old.accepted != new.accepted;
+                    let redraw_text = old.text != new.text || color_changed || old.wrap != new.wrap;
                     if redraw_outline || redraw_text {
                         self.print_at(pos, &mut printer)
                             .outline(outline_type(pos == self.selected))
-                            .color(side.color());
+                            .color(new.side.color());
                         if redraw_outline {
                             printer.draw_outline();
+                            draw_badge(&printer, new.accepted);
                         }
                         if redraw_text {
-                            printer.overwrite_text(old_text, text);
+                            let old_align = self.rtl.align(old.side, TextAlignH::Center);
+                            let align = self.rtl.align(new.side, TextAlignH::Center);
+                            if old_align == align && old.wrap == new.wrap {
+                                printer
+                                    .text_align_h(align)
+                                    .wrap(new.wrap)
+                                    .overwrite_text(old.text, new.text);
+                            } else {
+                                // Lines can't be overwritten in place when they move
+                                printer
+                                    .text_align_h(old_align)
+                                    .wrap(old.wrap)
+                                    .overwrite_text(old.text, "");
+                                printer
+                                    .text_align_h(align)
+                                    .wrap(new.wrap)
+                                    .draw_text(new.text);
+                            }
                         }
                     }
                 }
-                (Some((old_text, _)), None) => {
+                (Some(old), None) => {
                     self.print_at(pos, &mut printer)
                         .outline(Some(BoxOutline::ERASE))
+                        .text_align_h(self.rtl.align(old.side, TextAlignH::Center))
+                        .wrap(old.wrap)
                         .draw_outline()
-                        .overwrite_text(old_text, "");
+                        .overwrite_text(old.text, "");
                 }
                 (None, Some(_)) => {
                     self.print_card(pos, &mut printer);
@@ -144,6 +193,24 @@ impl<'a> FlashcardGrid<'a> {
     }
 }
 
+/// Marks the top right of the card `printer` is at with how many other answers are
+/// accepted, if there are any
+fn draw_badge(printer: &TextBox, accepted: usize) {
+    let badge = match accepted {
+        0 => return,
+        1..=9 => BADGE_DIGITS[accepted - 1],
+        _ => '⁺',
+    };
+    queue!(
+        io::stdout(),
+        cursor::MoveTo(printer.pos.x + printer.size.x - 2, printer.pos.y),
+        style::SetForegroundColor(printer.outline_color),
+        style::Print(badge),
+        style::SetForegroundColor(Color::Reset),
+    )
+    .unwrap();
+}
+
 fn outline_type(selected: bool) -> Option<BoxOutline> {
     Some(match selected {
         true => BoxOutline::DOUBLE,
@@ -171,17 +238,14 @@ impl<'a, 'b> FlashcardGridUpdater<'a, 'b> {
         self.0.selected = selected;
     }
 
-    pub fn fill_from_cards(
-        &mut self,
-        cards_iter: impl Iterator<Item = (&'a str, Side)>,
-    ) -> &mut Self {
+    pub fn fill_from_cards(&mut self, cards_iter: impl Iterator<Item = Face<'a>>) -> &mut Self {
         self.0.fill_from_cards(cards_iter);
         self
     }
 }
 
 impl<'a, 'b> Index<Vec2<u16>> for FlashcardGridUpdater<'a, 'b> {
-    type Output = Option<(&'a str, Side)>;
+    type Output = Option<Face<'a>>;
 
     fn index(&self, index: Vec2<u16>) -> &Self::Output {
         &self.0.cards[index.index_row_major(self.0.card_count.x as usize)]
@@ -1,26 +1,40 @@
 use std::{
-    io::{self, Write},
+    borrow::Cow,
+    cmp::Reverse,
+    fmt::{self, Display, Write as _},
+    fs::{self, File, OpenOptions},
+    io::{self, LineWriter, Write},
     path::PathBuf,
+    str::FromStr,
+    thread,
+    time::Duration,
 };
 
 use argh::FromArgs;
 use crossterm::{
     cursor,
-    event::{self, Event},
+    event::{Event, KeyCode, KeyEvent},
     queue,
-    style::{self, Color},
+    style::{self, Attribute, Color},
     terminal::{self, ClearType},
 };
-use rand::seq::SliceRandom;
-use text_box::{BoxOutline, TextBox};
+use rand::{
+    rngs::StdRng,
+    seq::{IteratorRandom, SliceRandom},
+    Rng, SeedableRng,
+};
+use text_box::{BoxOutline, MultiBoxOutline, TextBox};
 
 use crate::{
-    flashcards::{Flashcard, FlashcardText, RecallSettings, Set, Side},
+    flashcards::{Difficulty, Flashcard, FlashcardText, RecallSettings, RtlSettings, Set, Side},
+    input::events::Events,
     load_set,
-    output::{self, len_base10, text_box, MultiTextBox, Repeat, TerminalSettings},
+    output::{self, len_base10, text_box, MultiTextBox, Repeat, TerminalSettings, TextAlignH},
     vec2::Vec2,
 };
 
+mod examine;
+
 /// Learn a set
 #[derive(FromArgs, Debug)]
 #[argh(subcommand, name = "learn")]
@@ -28,6 +42,104 @@ pub struct Entry {
     /// the set to learn
     #[argh(positional)]
     set: PathBuf,
+    /// more sets to learn in the same session, using the first set's settings
+    #[argh(positional)]
+    more_sets: Vec<PathBuf>,
+    /// treat `set` as a url and download it (requires the "fetch" feature)
+    #[argh(switch)]
+    fetch: bool,
+    /// refuse to start if the set has warnings, like empty values or repeated terms
+    #[argh(switch)]
+    strict: bool,
+    /// show the set's title as the terminal window's title, which is left there after
+    /// exiting since terminals can't report their title
+    #[argh(switch)]
+    window_title: bool,
+    /// mark matching feedback with ✓ and ✗ so it doesn't rely on color alone
+    #[argh(switch)]
+    swap_colors: bool,
+    /// seed matching questions so every run with the same seed shows the same choices
+    #[argh(option)]
+    seed: Option<u64>,
+    /// print how many items would be studied, one for each side of each card that is
+    /// asked about, and exit without studying
+    #[argh(switch)]
+    count: bool,
+    /// browse through all the cards after finishing
+    #[argh(switch)]
+    examine: bool,
+    /// ask each card once, finishing it after it's answered whether or not the answer
+    /// was right
+    #[argh(switch)]
+    single_pass: bool,
+    /// go on to the next question shortly after a correct answer, instead of waiting
+    /// for a key
+    #[argh(switch)]
+    auto_advance: bool,
+    /// ask with the definition shown, recalling the term, before asking the other way
+    #[argh(switch)]
+    definition_first: bool,
+    /// learn every card with the term shown before asking any with the definition
+    /// shown, instead of mixing them (the default with --definition-first)
+    #[argh(switch)]
+    phased: bool,
+    /// prefer wrong matching answers that look like the right one
+    #[argh(switch)]
+    hard_distractors: bool,
+    /// show matching answers in alphabetical order instead of shuffling them, so the
+    /// same answers are always in the same place
+    #[argh(switch)]
+    sorted_answers: bool,
+    /// columns left empty on each side of the answers (default 4)
+    #[argh(option, default = "4")]
+    margin: u16,
+    /// lines left empty above, below, and between the boxes (default 2, at least 1)
+    #[argh(option, default = "2", from_str_fn(parse_gap))]
+    gap: u16,
+    /// keys that choose matching answers, from left to right: digits (1-4) or letters
+    /// (a-d) (default digits)
+    #[argh(option, default = "AnswerKeys::Digits")]
+    answer_keys: AnswerKeys,
+    /// where to put the progress bar: top or bottom (default bottom)
+    #[argh(option, default = "FooterPosition::Bottom")]
+    footer_position: FooterPosition,
+    /// show the set's title, or its file name if it has none, and how many cards it
+    /// has along the top
+    #[argh(switch)]
+    header: bool,
+    /// show how many cards are at each color of the progress bar on a line next to it
+    #[argh(switch)]
+    footer_numbers: bool,
+    /// record every input to a file so the session can be replayed
+    #[argh(option)]
+    record: Option<PathBuf>,
+    /// replay inputs recorded with --record before reading from the terminal
+    #[argh(option)]
+    replay: Option<PathBuf>,
+    /// stay out of raw mode and the alternate screen, and log every input to stderr,
+    /// so errors and events can be seen while debugging
+    #[argh(switch)]
+    no_raw_on_error: bool,
+    /// append a line to this file whenever a card is asked, answered, or moves along
+    /// the footer
+    #[argh(option)]
+    verbose: Option<PathBuf>,
+    /// after finishing, write the cards answered wrong at least once to this file as a
+    /// new set
+    #[argh(option)]
+    mistakes: Option<PathBuf>,
+}
+
+const MIN_TERM_SIZE: Vec2<u16> = Vec2::new(24, 24);
+
+/// How long a correct answer is shown before moving on with `--auto-advance`
+const AUTO_ADVANCE_DELAY: Duration = Duration::from_millis(600);
+
+fn parse_gap(s: &str) -> Result<u16, String> {
+    match s.parse::<u16>().map_err(|e| e.to_string())? {
+        0 => Err("Gap must be at least 1".to_owned()),
+        gap => Ok(gap),
+    }
 }
 
 const COLORS: [Color; 4] = [
@@ -39,45 +151,216 @@ const COLORS: [Color; 4] = [
 
 impl Entry {
     pub fn run(self) {
-        let set = load_set!(&self.set);
+        let mut set = load_set!(&self.set, fetch: self.fetch);
+        // Where each set's cards end in the merged set, to report on each separately
+        let mut deck_ends = vec![set.cards.len()];
+        for path in &self.more_sets {
+            let more = load_set!(path, fetch: self.fetch);
+            set.cards.extend(more.cards);
+            set.warnings.extend(more.warnings);
+            deck_ends.push(set.cards.len());
+        }
+        if self.strict {
+            super::exit_if_problems(&set, true);
+        }
+        if self.count {
+            match CardList::from_set(&set).cards.len() {
+                1 => println!("1 study item"),
+                count => println!("{count} study items"),
+            }
+            return;
+        }
         if set.cards.is_empty() {
             output::write_fatal_error("Set must have at least 1 card to learn");
             return;
         }
         let mut cards = CardList::from_set(&set);
-        let mut term_size: Vec2<_> = terminal::size()
-            .expect("unable to get terminal size")
-            .into();
+        cards.seed = self.seed;
+        if let Some(seed) = self.seed {
+            cards.rng = StdRng::seed_from_u64(seed);
+        }
+        cards.hard_distractors = self.hard_distractors;
+        cards.sorted_answers = self.sorted_answers;
+        if self.definition_first {
+            cards.first_side = Some(Side::Definition);
+        } else if self.phased {
+            cards.first_side = Some(Side::Term);
+        }
+        if let Some(path) = &self.verbose {
+            match OpenOptions::new().create(true).append(true).open(path) {
+                Ok(file) => cards.trace = Some(LineWriter::new(file)),
+                Err(err) => {
+                    output::write_fatal_error(&format!("Unable to open trace: {err}"));
+                    return;
+                }
+            }
+        }
+        let layout = Layout {
+            margin: self.margin,
+            gap: self.gap,
+            footer: self.footer_position,
+            footer_numbers: self.footer_numbers,
+            header: self.header,
+        };
+        let min_term_size = layout.min_term_size();
+        let mut term_size = match output::terminal_size_at_least(min_term_size) {
+            Some(size) => size,
+            None => return,
+        };
+        let mut events = match Events::open(self.record.as_deref(), self.replay.as_deref()) {
+            Some(events) => events,
+            None => return,
+        };
+        events.log = self.no_raw_on_error;
+
         let mut term_settings = TerminalSettings::new();
-        term_settings
-            .enter_alternate_screen()
-            .enable_raw_mode()
-            .hide_cursor();
-        let mut asker = Asker::new(term_size);
+        if !self.no_raw_on_error {
+            term_settings
+                .enter_alternate_screen()
+                .enable_raw_mode()
+                .enable_bracketed_paste()
+                .hide_cursor();
+        }
+        if let (true, Some(title)) = (self.window_title, &set.metadata.title) {
+            term_settings.set_title(title);
+        }
+        let mut asker = Asker::new(term_size, layout);
+        asker.feedback_marks = self.swap_colors;
+        if self.header {
+            let names = match &set.metadata.title {
+                // More sets use the first set's settings, so its title names them too
+                Some(title) => title.clone(),
+                None => std::iter::once(&self.set)
+                    .chain(&self.more_sets)
+                    .map(|path| {
+                        path.file_name()
+                            .unwrap_or(path.as_os_str())
+                            .to_string_lossy()
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            };
+            let cards = match set.cards.len() {
+                1 => "1 card".to_owned(),
+                count => format!("{count} cards"),
+            };
+            asker.header = Some(format!("{names} — {cards}"));
+        }
 
-        while let Some(card) = cards.get_unstudied() {
+        while let Some((index, card)) = cards.get_unstudied() {
+            let status = cards.cards[index].status();
+            cards.trace_item(index, format_args!("asked as {status}"));
+            asker.set_wrap(cards.cards[index].card.wrap);
+            asker.set_rtl(set.rtl, cards.cards[index].side);
             match card {
                 AskerData::Matching {
                     question,
                     answers,
                     correct_answer,
                 } => {
+                    queue!(io::stdout(), terminal::Clear(ClearType::All)).unwrap();
                     asker.draw_matching(question, answers);
-                    cards.print_footer(term_size);
+                    asker.draw_status(&status);
+                    cards.print_footer(term_size, layout);
                     io::stdout().flush().unwrap();
+                    let mut chosen = None;
                     loop {
-                        match event::read().expect("Unable to read event") {
+                        match events.read() {
                             crate::esc!() => panic!("Exited app"),
-                            Event::Resize(w, h) => {
+                            crate::known!() => {
+                                cards.mark_known(index);
+                                break;
+                            }
+                            event @ (Event::Resize(..)
+                            | crate::pause!()
+                            | Event::Key(KeyEvent {
+                                code: KeyCode::Char('+' | '=' | '-' | '_' | 'o' | 'O'),
+                                ..
+                            })) => {
                                 queue!(io::stdout(), terminal::Clear(ClearType::All)).unwrap();
-                                if w < 24 || h < 24 {
-                                    continue;
+                                match event {
+                                    Event::Resize(w, h) => {
+                                        term_size = super::wait_for_size(
+                                            &mut events,
+                                            Vec2::new(w, h),
+                                            min_term_size,
+                                        );
+                                    }
+                                    crate::pause!() => {
+                                        let size = super::pause(&mut events);
+                                        term_size =
+                                            super::wait_for_size(&mut events, size, min_term_size);
+                                    }
+                                    Event::Key(KeyEvent {
+                                        code: KeyCode::Char('+' | '='),
+                                        ..
+                                    }) => asker.move_split(1),
+                                    Event::Key(KeyEvent {
+                                        code: KeyCode::Char('o' | 'O'),
+                                        ..
+                                    }) => asker.cycle_outline(),
+                                    _ => asker.move_split(-1),
                                 }
-                                term_size = Vec2::new(w, h);
                                 asker.resize_to(term_size);
-                                asker.draw_matching(question, answers);
-                                cards.print_footer(term_size);
+                                match chosen {
+                                    Some(chosen) => asker.draw_matching_result(
+                                        question,
+                                        answers,
+                                        correct_answer,
+                                        chosen,
+                                    ),
+                                    None => asker.draw_matching(question, answers),
+                                };
+                                asker.draw_status(&status);
+                                cards.print_footer(term_size, layout);
+                                io::stdout().flush().unwrap();
+                            }
+                            Event::Key(_) if chosen.is_some() => {
+                                if self.single_pass {
+                                    cards.mark_known(index);
+                                }
+                                break;
+                            }
+                            Event::Key(KeyEvent {
+                                code: KeyCode::Char(c),
+                                ..
+                            }) if self.answer_keys.answer(c).is_some() => {
+                                let answer = self.answer_keys.answer(c).unwrap();
+                                chosen = Some(answer);
+                                // Empty answers are only there to fill space
+                                let correct = !answers[answer].is_empty()
+                                    && correct_answer
+                                        .contains(answers[answer], RecallSettings::default());
+                                let result = match correct {
+                                    true => "correctly",
+                                    false => "wrong",
+                                };
+                                cards.trace_item(
+                                    index,
+                                    format_args!("answered {:?} {result}", answers[answer]),
+                                );
+                                cards.record_answer(index, correct);
+                                queue!(io::stdout(), terminal::Clear(ClearType::All)).unwrap();
+                                asker.draw_matching_result(
+                                    question,
+                                    answers,
+                                    correct_answer,
+                                    answer,
+                                );
+                                asker.draw_status(&status);
+                                cards.print_footer(term_size, layout);
                                 io::stdout().flush().unwrap();
+                                // Don't let keys pressed before the result was shown skip it
+                                events.discard_queued_keys();
+                                if correct && self.auto_advance {
+                                    thread::sleep(AUTO_ADVANCE_DELAY);
+                                    // Or while it was shown before moving on
+                                    events.discard_queued_keys();
+                                    if self.single_pass {
+                                        cards.mark_known(index);
+                                    }
+                                    break;
+                                }
                             }
                             _ => {}
                         }
@@ -86,8 +369,40 @@ impl Entry {
             }
         }
 
-        io::stdin().read_line(&mut String::new()).unwrap();
+        cards.print_heatmap(term_size);
+        if self.examine {
+            // Leave the heatmap up until a key is pressed
+            while !matches!(events.read(), Event::Key(_)) {}
+            examine::examine(&set.cards, term_size, &mut events);
+        } else {
+            io::stdin().read_line(&mut String::new()).unwrap();
+        }
         drop(term_settings);
+
+        if !self.more_sets.is_empty() {
+            let failed = cards.failed();
+            let paths = std::iter::once(&self.set).chain(&self.more_sets);
+            let mut start = 0;
+            for (path, end) in paths.zip(deck_ends) {
+                let fails = failed[start..end].iter().filter(|failed| **failed).count();
+                println!(
+                    "{}: {fails} of {} cards answered wrong",
+                    path.display(),
+                    end - start
+                );
+                start = end;
+            }
+        }
+
+        if let Some(path) = &self.mistakes {
+            let mistakes = Set {
+                cards: cards.fails().cloned().collect(),
+                ..set.clone()
+            };
+            if let Err(err) = fs::write(path, mistakes.to_string()) {
+                output::write_fatal_error(&format!("Unable to write {}: {err}", path.display()));
+            }
+        }
     }
 }
 
@@ -95,53 +410,159 @@ impl Entry {
 struct CardList<'a> {
     cards: Vec<CardListItem<'a>>,
     set: &'a Set,
+    /// If set, matching questions are generated deterministically from this
+    seed: Option<u64>,
+    /// Chooses which card to ask next, and how to ask it if there's no seed.  Seeded
+    /// from `seed` if there is one, so the whole session can be repeated
+    rng: StdRng,
+    /// Where to write a line for each change to the state of the cards
+    trace: Option<LineWriter<File>>,
+    /// If set, items showing this side are all asked before any showing the other
+    first_side: Option<Side>,
+    /// Choose wrong matching answers that are similar to the right one instead of
+    /// choosing them at random
+    hard_distractors: bool,
+    /// Put matching answers in alphabetical order instead of shuffling them
+    sorted_answers: bool,
 }
 
 #[derive(Debug)]
 struct CardListItem<'a> {
     card: &'a Flashcard,
+    /// The index of `card` in the set
+    index: usize,
     side: Side,
     next_study_type: StudyType,
     footer_color: u8,
+    /// If this has been answered wrong
+    failed: bool,
+}
+
+impl CardListItem<'_> {
+    /// Returns true if this has been fully learned and shouldn't be asked again
+    fn is_complete(&self) -> bool {
+        self.footer_color as usize == COLORS.len() - 1
+    }
+
+    /// A short description of how this is being studied, like "Matching (1/3)"
+    fn status(&self) -> String {
+        format!(
+            "{} ({}/{})",
+            self.next_study_type,
+            self.footer_color + 1,
+            COLORS.len() - 1
+        )
+    }
 }
 
 impl<'a> CardList<'a> {
     fn from_set(set: &'a Set) -> Self {
-        let count = [set.recall_t.is_used(), set.recall_d.is_used()]
+        let v = [Side::Term, Side::Definition]
             .into_iter()
-            .filter(|b| *b)
-            .count();
-        let mut v = Vec::with_capacity(count * set.cards.len());
-        if set.recall_t.is_used() {
-            let next_study_type = if set.recall_t.matching {
-                StudyType::Matching(0)
-            } else {
-                StudyType::Text(0)
-            };
-            v.extend(set.cards.iter().map(|card| CardListItem {
-                card,
-                side: Side::Definition,
-                next_study_type,
-                footer_color: 0,
-            }));
-        }
-        if set.recall_d.is_used() {
-            let next_study_type = if set.recall_d.matching {
-                StudyType::Matching(0)
-            } else {
-                StudyType::Text(0)
-            };
-            v.extend(set.cards.iter().map(|card| CardListItem {
-                card,
-                side: Side::Term,
-                next_study_type,
-                footer_color: 0,
-            }));
+            .flat_map(|side| {
+                set.cards_for_side(side)
+                    .map(move |(index, card)| CardListItem {
+                        card,
+                        index,
+                        side: !side,
+                        next_study_type: StudyType::first(set.recall(side), card.difficulty),
+                        // Easy cards start a color along, so they need one fewer
+                        // correct answer to finish
+                        footer_color: match card.difficulty {
+                            Difficulty::Easy => 1,
+                            Difficulty::Normal | Difficulty::Hard => 0,
+                        },
+                        failed: false,
+                    })
+            })
+            .collect();
+        Self {
+            cards: v,
+            set,
+            seed: None,
+            rng: StdRng::from_entropy(),
+            trace: None,
+            first_side: None,
+            hard_distractors: false,
+            sorted_answers: false,
+        }
+    }
+
+    /// Returns each card that has been answered wrong, once even if it was asked
+    /// about both sides, in the order they appear in the set
+    fn fails(&self) -> impl Iterator<Item = &'a Flashcard> + '_ {
+        self.set
+            .cards
+            .iter()
+            .zip(self.failed())
+            .filter_map(|(card, failed)| failed.then_some(card))
+    }
+
+    /// Returns if each card in the set has been answered wrong about either side
+    fn failed(&self) -> Vec<bool> {
+        self.fail_counts()
+            .into_iter()
+            .map(|fails| fails > 0)
+            .collect()
+    }
+
+    /// Returns how many sides of each card in the set have been answered wrong
+    fn fail_counts(&self) -> Vec<usize> {
+        let mut fail_counts = vec![0; self.set.cards.len()];
+        for item in self.cards.iter().filter(|item| item.failed) {
+            fail_counts[item.index] += 1;
         }
-        Self { cards: v, set }
+        fail_counts
     }
 
-    fn print_footer(&self, term_size: Vec2<u16>) {
+    /// Clears the screen and draws a cell for each card in the set, colored like the
+    /// footer by how many of its sides were answered wrong.  Cards past the bottom of
+    /// the screen are left out
+    fn print_heatmap(&self, term_size: Vec2<u16>) {
+        const CELL_WIDTH: u16 = 3;
+
+        queue!(
+            io::stdout(),
+            terminal::Clear(ClearType::All),
+            cursor::MoveTo(0, 0),
+            style::Print("Mistakes per card"),
+        )
+        .unwrap();
+        let per_row = (term_size.x / CELL_WIDTH).max(1) as usize;
+        let rows = term_size.y.saturating_sub(2) as usize;
+        for (index, fails) in self.fail_counts().into_iter().enumerate() {
+            let pos = Vec2::new((index % per_row) as u16, (index / per_row) as u16);
+            if pos.y as usize >= rows {
+                break;
+            }
+            let color = COLORS[(COLORS.len() - 1).saturating_sub(fails)];
+            queue!(
+                io::stdout(),
+                cursor::MoveTo(pos.x * CELL_WIDTH, pos.y + 2),
+                style::SetBackgroundColor(color),
+                style::Print(Repeat(' ', CELL_WIDTH - 1)),
+                style::SetBackgroundColor(Color::Reset),
+            )
+            .unwrap();
+        }
+        io::stdout().flush().unwrap();
+    }
+
+    /// Writes a line about the item at `index` to the trace, if there is one
+    fn trace_item(&mut self, index: usize, message: fmt::Arguments) {
+        if let Some(trace) = &mut self.trace {
+            let item = &self.cards[index];
+            writeln!(
+                trace,
+                "{:?}, recalling {}: {message}",
+                item.card[Side::Term].canonical(),
+                !item.side,
+            )
+            .expect("Unable to write trace");
+        }
+    }
+
+    fn print_footer(&self, term_size: Vec2<u16>, layout: Layout) {
         let mut counts = [0; COLORS.len()];
         for item in self.cards.iter() {
             counts[item.footer_color as usize] += 1;
@@ -152,10 +573,35 @@ impl<'a> CardList<'a> {
         let mut widths = fractions.map(|f| (f * term_size.x as f32) as u16);
         widths[0] = term_size.x - widths[1..].iter().sum::<u16>();
 
-        queue!(io::stdout(), cursor::MoveTo(0, term_size.y - 1)).unwrap();
+        let header = layout.header as u16;
+        let y = match layout.footer {
+            FooterPosition::Top => header,
+            FooterPosition::Bottom => term_size.y - 1,
+        };
+        if layout.footer_numbers {
+            let numbers_y = match layout.footer {
+                FooterPosition::Top => header + 1,
+                FooterPosition::Bottom => term_size.y - 2,
+            };
+            let [black, red, yellow, green] = counts;
+            queue!(
+                io::stdout(),
+                cursor::MoveTo(0, numbers_y),
+                terminal::Clear(ClearType::CurrentLine),
+                style::Print(format!("B:{black} R:{red} Y:{yellow} G:{green}")),
+            )
+            .unwrap();
+        }
+        // On narrow terminals some counts won't fit, so show just the colored bar
+        // rather than numbers on only some sections
+        let show_counts = counts
+            .iter()
+            .zip(widths)
+            .all(|(&count, width)| count == 0 || len_base10(count) <= width);
+        queue!(io::stdout(), cursor::MoveTo(0, y)).unwrap();
         for ((count, width), color) in counts.into_iter().zip(widths).zip(COLORS).rev() {
             let len_base10_u16 = len_base10(count);
-            if count > 0 && len_base10_u16 <= width {
+            if show_counts && count > 0 {
                 let remaining_len = width - len_base10_u16;
                 let before_len = remaining_len / 2;
                 let after_len = remaining_len - before_len;
@@ -179,83 +625,477 @@ impl<'a> CardList<'a> {
         queue!(io::stdout(), style::SetBackgroundColor(Color::Reset)).unwrap();
     }
 
-    fn get_unstudied(&self) -> Option<AskerData> {
-        let mut rng = rand::thread_rng();
-        self.cards
-            .choose(&mut rng)
-            .map(|card| match card.next_study_type {
-                StudyType::Matching(_) => {
-                    let correct_answer = &card.card[!card.side];
-                    let mut answers = [""; 4];
-                    answers[0] = correct_answer.display();
-                    for i in 1..4 {
-                        for _ in 0..12 {
-                            answers[i] =
-                                self.set.cards.choose(&mut rng).unwrap()[!card.side].display();
-                            if !answers[..i].contains(&answers[i]) {
-                                break;
-                            }
-                        }
-                    }
-                    answers.shuffle(&mut rng);
-                    AskerData::Matching {
-                        question: card.card[card.side].display(),
-                        answers,
-                        correct_answer,
-                    }
-                }
-                StudyType::Text(_) => todo!(),
-            })
+    /// Moves the item at `index` one color closer to finished after a correct answer,
+    /// once it has no extra correct answers left to give at its study type.  Wrong
+    /// answers mark it as failed
+    fn record_answer(&mut self, index: usize, correct: bool) {
+        let item = &mut self.cards[index];
+        if !correct {
+            item.failed = true;
+            return;
+        }
+        match &mut item.next_study_type {
+            StudyType::Matching(extra) | StudyType::Text(extra) if *extra > 0 => {
+                *extra -= 1;
+                let extra = *extra;
+                self.trace_item(index, format_args!("{extra} extra answers left"));
+            }
+            _ => {
+                let old_color = item.footer_color;
+                let new_color = (old_color + 1).min((COLORS.len() - 1) as u8);
+                item.footer_color = new_color;
+                self.trace_item(index, format_args!("footer {old_color} -> {new_color}"));
+            }
+        }
+    }
+
+    /// Marks the item at `index` as fully learned, so it won't be asked again
+    fn mark_known(&mut self, index: usize) {
+        let old_color = self.cards[index].footer_color;
+        let new_color = (COLORS.len() - 1) as u8;
+        self.cards[index].footer_color = new_color;
+        self.trace_item(
+            index,
+            format_args!("marked known, footer {old_color} -> {new_color}"),
+        );
+    }
+
+    /// Chooses an item that hasn't been fully learned, returning its index and how to
+    /// ask about it
+    fn get_unstudied(&mut self) -> Option<(usize, AskerData<'a>)> {
+        let unstudied = || {
+            self.cards
+                .iter()
+                .enumerate()
+                .filter(|(_, card)| !card.is_complete())
+        };
+        let first = unstudied()
+            .filter(|(_, card)| Some(card.side) == self.first_side)
+            .map(|(index, _)| index)
+            .choose(&mut self.rng);
+        let index = match first {
+            Some(index) => index,
+            None => unstudied().map(|(index, _)| index).choose(&mut self.rng)?,
+        };
+        let mut rng = self.card_rng(index);
+        let card = &self.cards[index];
+        let data = match card.next_study_type {
+            StudyType::Matching(_) => AskerData::Matching {
+                question: card.card[card.side].display_with(&mut rng),
+                answers: self.matching_answers_for(card, &mut rng),
+                correct_answer: &card.card[!card.side],
+            },
+            StudyType::Text(_) => todo!(),
+        };
+        Some((index, data))
+    }
+
+    /// Returns the rng to use when asking about the item at `index`.  If this has a
+    /// seed, the rng is seeded from it and the card so the same card is always asked
+    /// the same way
+    fn card_rng(&mut self, index: usize) -> StdRng {
+        let item = &self.cards[index];
+        match self.seed {
+            Some(seed) => {
+                let card_seed = item.index as u64 * 2 + (item.side == Side::Term) as u64;
+                StdRng::seed_from_u64(seed ^ card_seed)
+            }
+            None => StdRng::from_rng(&mut self.rng).unwrap(),
+        }
+    }
+
+    /// Returns the correct answer and 3 distractors in a random order, or sorted
+    /// ignoring case with empty answers last if `sorted_answers` is set.  Distractors
+    /// are drawn uniformly from the other cards, and are never accepted answers for
+    /// `item`, the text of its question, or duplicates of each other.  If there
+    /// aren't enough distinct distractors, the remaining answers are empty
+    fn matching_answers_for(&self, item: &CardListItem<'a>, rng: &mut impl Rng) -> [&'a str; 4] {
+        let correct_answer = &item.card[!item.side];
+        let mut answers = [""; 4];
+        answers[0] = correct_answer.display_with(rng);
+
+        let mut others: Vec<_> = (0..self.set.cards.len())
+            .filter(|&index| index != item.index)
+            .collect();
+        others.shuffle(rng);
+        if self.hard_distractors {
+            // Stable, so equally similar cards stay in random order
+            others.sort_by_cached_key(|&index| {
+                similarity(answers[0], self.set.cards[index][!item.side].canonical())
+            });
+        }
+        let mut len = 1;
+        for index in others {
+            if len == answers.len() {
+                break;
+            }
+            let answer = self.set.cards[index][!item.side].display_with(rng);
+            // A distractor matching the question would look like the right answer when
+            // a card's term and definition are the same
+            if !correct_answer.contains(answer, RecallSettings::default())
+                && !item.card[item.side].contains(answer, RecallSettings::default())
+                && !answers[..len].contains(&answer)
+            {
+                answers[len] = answer;
+                len += 1;
+            }
+        }
+
+        match self.sorted_answers {
+            true => answers.sort_by_cached_key(|answer| (answer.is_empty(), answer.to_lowercase())),
+            false => answers.shuffle(rng),
+        }
+        answers
     }
 
     fn recall_settings(&self, side: Side) -> RecallSettings {
-        match side {
-            Side::Term => self.set.recall_t,
-            Side::Definition => self.set.recall_d,
+        self.set.recall(side)
+    }
+}
+
+/// Joins `prefix` and up to 3 of `items` with commas, ending with "+N more" if any
+/// are left out.  Stops early rather than go over `width` characters if possible.
+/// Empty items are skipped, so they aren't counted as more
+fn capped_list(prefix: &str, items: &[String], width: usize) -> String {
+    const MAX_SHOWN: usize = 3;
+
+    let items: Vec<_> = items.iter().filter(|item| !item.is_empty()).collect();
+    let mut text = prefix.to_owned();
+    for (index, item) in items.iter().enumerate() {
+        let remaining = items.len() - index - 1;
+        let more = match remaining {
+            0 => String::new(),
+            remaining => format!(", +{remaining} more"),
+        };
+        let separator = if index == 0 { "" } else { ", " };
+        let fits =
+            text.chars().count() + separator.len() + item.chars().count() + more.len() <= width;
+        if index == MAX_SHOWN || (index > 0 && !fits) {
+            write!(text, ", +{} more", items.len() - index).unwrap();
+            break;
         }
+        write!(text, "{separator}{item}").unwrap();
     }
+    text
+}
+
+/// Returns a key that sorts texts more similar to `target` first.  Texts are more
+/// similar if they share a longer prefix with `target`, then if their length is closer
+fn similarity(target: &str, text: &str) -> (Reverse<usize>, usize) {
+    let prefix_len = target
+        .chars()
+        .flat_map(char::to_lowercase)
+        .zip(text.chars().flat_map(char::to_lowercase))
+        .take_while(|(a, b)| a == b)
+        .count();
+    let len_difference = target.chars().count().abs_diff(text.chars().count());
+    (Reverse(prefix_len), len_difference)
 }
 
 #[derive(Debug)]
 struct Asker {
+    /// Shows which set is being learned, if `header` is set
+    header_box: TextBox,
+    header: Option<String>,
+    status_box: TextBox,
     question_box: TextBox,
+    /// Lists the other accepted answers once a question has been answered
+    accepted_box: TextBox,
     matching_answers_box: MultiTextBox,
+    /// Prefix matching feedback with ✓ and ✗ in addition to coloring it
+    feedback_marks: bool,
+    layout: Layout,
+    /// How many lines taller the question box is than the answers box is
+    split: i16,
+    /// The index in [`BoxOutline::STYLES`] of the style boxes are outlined with
+    outline_style: usize,
 }
 
 impl Asker {
-    fn new(term_size: Vec2<u16>) -> Self {
+    fn new(term_size: Vec2<u16>, layout: Layout) -> Self {
         let mut this = Self {
+            header_box: TextBox::new(),
+            header: None,
+            status_box: TextBox::new(),
             question_box: TextBox::new(),
+            accepted_box: TextBox::new(),
             matching_answers_box: MultiTextBox::new(),
+            feedback_marks: false,
+            layout,
+            split: 0,
+            outline_style: 2,
         };
-        this.question_box.outline(Some(BoxOutline::DOUBLE)).y(2);
+        let top = layout.top();
+        this.header_box.outline(None).height(1);
+        this.status_box
+            .outline(None)
+            .height(1)
+            .y(top + layout.gap - 1);
+        this.accepted_box.outline(None).height(1).x(layout.margin);
+        this.question_box
+            .outline(Some(BoxOutline::STYLES[this.outline_style]))
+            .set_attribute(Attribute::Bold)
+            .y(top + layout.gap);
         this.matching_answers_box
-            .x(4)
-            .box_count(Vec2::new(4, 1))
+            .x(layout.margin)
+            .outline(MultiBoxOutline::STYLES[this.outline_style])
             .number(true);
         this.resize_to(term_size);
         this
     }
 
     fn resize_to(&mut self, term_size: Vec2<u16>) -> &mut Self {
-        let inner_y = term_size.y - 7;
-        let box_height = inner_y / 2;
+        let Layout { margin, gap, .. } = self.layout;
+        let bottom = term_size.y - self.layout.bottom();
+        // A gap above, below, and between the boxes, and lines for the header and footer
+        let inner_y = term_size
+            .y
+            .saturating_sub(gap * 3 + self.layout.top() + self.layout.bottom());
+        let boxes_height = inner_y / 2 * 2;
+        // The minimum terminal size keeps `boxes_height` at least 6, so both boxes can
+        // be at least 3 tall
+        let question_height =
+            (boxes_height as i16 / 2 + self.split / 2).clamp(3, boxes_height as i16 - 3) as u16;
+        self.split = question_height as i16 * 2 - boxes_height as i16;
+        let answers_height = boxes_height - question_height;
+        let answers_width = term_size.x.saturating_sub(margin * 2);
+        self.status_box.width(term_size.x / 3).x(term_size.x / 3);
+        self.header_box.width(term_size.x);
         self.question_box
             .width(term_size.x / 3)
             .x(term_size.x / 3)
-            .height(box_height);
+            .height(question_height);
+        self.accepted_box
+            .width(answers_width)
+            .y(self.question_box.pos.y + question_height);
+        // Terminal cells are about twice as tall as they are wide, so answers are
+        // stacked when the terminal looks taller than it is wide and there's room for
+        // a line of text in each one
+        let box_count = match term_size.x < term_size.y * 2 && answers_height >= 9 {
+            true => Vec2::new(1, 4),
+            false => Vec2::new(4, 1),
+        };
+        self.matching_answers_box
+            .box_count(box_count)
+            .width(answers_width)
+            .height(answers_height)
+            .y(bottom.saturating_sub(gap + answers_height));
+        self
+    }
+
+    /// Moves the line between the question and answers boxes down by `lines`, or up
+    /// if it's negative.  Takes effect the next time this is resized
+    fn move_split(&mut self, lines: i16) {
+        self.split = self.split.saturating_add(lines * 2);
+    }
+
+    /// Outlines the boxes with the next style in [`BoxOutline::STYLES`], so it's easy
+    /// to find one that draws well in the terminal.  Takes effect the next time this
+    /// is drawn
+    fn cycle_outline(&mut self) {
+        self.outline_style = (self.outline_style + 1) % BoxOutline::STYLES.len();
+        self.question_box
+            .outline(Some(BoxOutline::STYLES[self.outline_style]));
         self.matching_answers_box
-            .width(term_size.x - 8)
-            .height(box_height)
-            .y(term_size.y - 3 - box_height);
+            .outline(MultiBoxOutline::STYLES[self.outline_style]);
+    }
+
+    /// Sets if the question and answers are wrapped or cut off at the edge of their boxes
+    fn set_wrap(&mut self, wrap: bool) -> &mut Self {
+        self.question_box.wrap(wrap);
+        self.matching_answers_box.wrap(wrap);
+        self
+    }
+
+    /// Aligns the question, from `question_side`, and the answers, from the other
+    /// side, to the right of their boxes if they're written right to left
+    fn set_rtl(&mut self, rtl: RtlSettings, question_side: Side) -> &mut Self {
+        self.question_box
+            .text_align_h(rtl.align(question_side, TextAlignH::Center));
+        self.matching_answers_box
+            .text_align_h(rtl.align(!question_side, TextAlignH::Center));
+        self
+    }
+
+    /// Draws `question` in a box only as tall as it needs, up to the height set aside
+    /// for it, and centered in that space, so short questions don't leave a large
+    /// empty box
+    fn draw_question(&self, question: &str) -> &Self {
+        let mut question_box = self.question_box.clone();
+        let max_height = question_box.size.y;
+        question_box.fit_height_to(question, max_height);
+        question_box
+            .y(question_box.pos.y + max_height.saturating_sub(question_box.size.y) / 2)
+            .draw_outline_and_text(question);
         self
     }
 
     pub fn draw_matching(&self, question: &str, answers: [&str; 4]) -> &Self {
-        self.question_box.draw_outline_and_text(question);
+        self.draw_question(question);
         self.matching_answers_box.draw_outline().draw_text(answers);
         self
     }
+
+    /// Draws `status`, and the header if there is one
+    pub fn draw_status(&self, status: &str) -> &Self {
+        self.status_box.draw_text(status);
+        if let Some(header) = &self.header {
+            self.header_box.draw_text(header);
+        }
+        self
+    }
+
+    /// Draws a matching question with the correct answers highlighted, and `chosen`
+    /// highlighted as wrong if it isn't correct
+    pub fn draw_matching_result(
+        &self,
+        question: &str,
+        answers: [&str; 4],
+        correct_answer: &FlashcardText,
+        chosen: usize,
+    ) -> &Self {
+        let mut boxes = answers.map(|answer| {
+            (
+                Cow::Borrowed(answer),
+                self.matching_answers_box.content_color,
+            )
+        });
+        for (index, (answer, color)) in boxes.iter_mut().enumerate() {
+            let mark = if !answer.is_empty()
+                && correct_answer.contains(answer, RecallSettings::default())
+            {
+                *color = Color::Green;
+                '✓'
+            } else if index == chosen {
+                *color = Color::Red;
+                '✗'
+            } else {
+                continue;
+            };
+            if self.feedback_marks {
+                *answer = Cow::Owned(format!("{mark} {answer}"));
+            }
+        }
+
+        self.draw_question(question);
+        let accepted = correct_answer.readable_accepted();
+        if !accepted.is_empty() {
+            self.accepted_box.draw_text(&capped_list(
+                "Also accepted: ",
+                &accepted,
+                self.accepted_box.inner_size().x as usize,
+            ));
+        }
+        self.matching_answers_box.draw_outline().draw_text_colored(
+            boxes
+                .iter()
+                .map(|(answer, color)| (answer.as_ref(), *color)),
+        );
+        self
+    }
+}
+
+/// Spacing around and between the boxes
+#[derive(Debug, Clone, Copy)]
+struct Layout {
+    /// Columns left empty on each side of the answers
+    margin: u16,
+    /// Lines left empty above, below, and between the boxes
+    gap: u16,
+    footer: FooterPosition,
+    /// If the footer has a line with the number of cards at each color
+    footer_numbers: bool,
+    /// If the first line shows which set is being learned
+    header: bool,
+}
+
+impl Layout {
+    /// How many lines the footer takes
+    fn footer_lines(self) -> u16 {
+        1 + self.footer_numbers as u16
+    }
+
+    /// How many lines at the top of the screen are taken by the header and footer
+    fn top(self) -> u16 {
+        self.header as u16
+            + match self.footer {
+                FooterPosition::Top => self.footer_lines(),
+                FooterPosition::Bottom => 0,
+            }
+    }
+
+    /// How many lines at the bottom of the screen are taken by the footer
+    fn bottom(self) -> u16 {
+        match self.footer {
+            FooterPosition::Top => 0,
+            FooterPosition::Bottom => self.footer_lines(),
+        }
+    }
+
+    /// The smallest terminal the boxes fit in with this spacing
+    fn min_term_size(self) -> Vec2<u16> {
+        Vec2::new(
+            MIN_TERM_SIZE
+                .x
+                .max(self.margin.saturating_mul(2).saturating_add(16)),
+            MIN_TERM_SIZE.y.max(
+                self.gap
+                    .saturating_mul(3)
+                    .saturating_add(6 + self.top() + self.bottom()),
+            ),
+        )
+    }
+}
+
+/// Which edge of the screen the progress footer is drawn along
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FooterPosition {
+    Top,
+    Bottom,
+}
+
+impl FromStr for FooterPosition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "top" => Ok(FooterPosition::Top),
+            "bottom" => Ok(FooterPosition::Bottom),
+            _ => Err("expected \"top\" or \"bottom\"".to_owned()),
+        }
+    }
+}
+
+/// The keys used to choose matching answers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AnswerKeys {
+    Digits,
+    Letters,
+}
+
+impl AnswerKeys {
+    /// Returns the index of the answer `c` chooses, if it chooses one
+    fn answer(self, c: char) -> Option<usize> {
+        let (first, c) = match self {
+            AnswerKeys::Digits => ('1', c),
+            AnswerKeys::Letters => ('a', c.to_ascii_lowercase()),
+        };
+        (c as usize)
+            .checked_sub(first as usize)
+            .filter(|&answer| answer < 4)
+    }
+}
+
+impl FromStr for AnswerKeys {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "digits" => Ok(AnswerKeys::Digits),
+            "letters" => Ok(AnswerKeys::Letters),
+            _ => Err("expected \"digits\" or \"letters\"".to_owned()),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -274,8 +1114,100 @@ enum AskerData<'a> {
     },
 }
 
+/// How an item is asked, with how many extra correct answers it needs before its
+/// footer color moves along
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 enum StudyType {
     Matching(u8),
     Text(u8),
 }
+
+impl StudyType {
+    /// The study type a card starts at.  Hard cards always start with matching, and
+    /// need an extra correct answer there.  Easy cards start like normal ones, since
+    /// text questions can't be studied yet
+    fn first(recall: RecallSettings, difficulty: Difficulty) -> Self {
+        match (difficulty, recall.matching) {
+            (Difficulty::Hard, _) => StudyType::Matching(1),
+            (_, true) => StudyType::Matching(0),
+            (_, false) => StudyType::Text(0),
+        }
+    }
+}
+
+impl Display for StudyType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use StudyType::*;
+        match self {
+            Matching(_) => write!(f, "Matching"),
+            Text(_) => write!(f, "Text"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn harder_cards_need_more_correct_answers() {
+        let set: Set = "[recall_t]\nmatching\n\n\
+            T: a\nD: 1\ndiff: easy\n\nT: b\nD: 2\n\nT: c\nD: 3\ndiff: hard\n"
+            .parse()
+            .unwrap();
+        let mut cards = CardList::from_set(&set);
+        let answers_needed = (0..cards.cards.len())
+            .map(|index| {
+                let mut answers = 0;
+                cards.record_answer(index, false);
+                while !cards.cards[index].is_complete() {
+                    cards.record_answer(index, true);
+                    answers += 1;
+                }
+                answers
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(answers_needed, [2, 3, 4]);
+    }
+
+    #[test]
+    fn capped_list_skips_blanks_and_counts_the_rest() {
+        let items = ["cafe", "", "café", "coffee", "java", ""].map(str::to_owned);
+        assert_eq!(
+            capped_list("Also: ", &items, 80),
+            "Also: cafe, café, coffee, +1 more"
+        );
+        assert_eq!(capped_list("Also: ", &items[..3], 80), "Also: cafe, café");
+    }
+
+    #[test]
+    fn correct_matching_answer_is_in_every_slot_equally() {
+        const RUNS: usize = 8000;
+
+        let set: Set = "[recall_t]\nmatching\n\n\
+            T: a\nD: 1\n\nT: b\nD: 2\n\nT: c\nD: 3\n\nT: d\nD: 4\n\n\
+            T: e\nD: 5\n\nT: f\nD: 6\n\nT: g\nD: 7\n\nT: h\nD: 8\n"
+            .parse()
+            .unwrap();
+        let cards = CardList::from_set(&set);
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut slots = [0; 4];
+        for run in 0..RUNS {
+            let item = &cards.cards[run % cards.cards.len()];
+            let answers = cards.matching_answers_for(item, &mut rng);
+            let correct = item.card[!item.side].canonical();
+            assert_eq!(
+                answers.iter().filter(|&&answer| answer == correct).count(),
+                1
+            );
+            slots[answers
+                .iter()
+                .position(|&answer| answer == correct)
+                .unwrap()] += 1;
+        }
+        // Over 5 standard deviations either way, so a fair shuffle won't fail by chance
+        for count in slots {
+            assert!((1800..=2200).contains(&count), "{slots:?}");
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::io::{self, Write};
+
+use crossterm::{
+    event::Event,
+    queue, style,
+    terminal::{self, ClearType},
+};
+
+use crate::{
+    flashcards::{Flashcard, Side},
+    input::events::Events,
+    output::{select_list::SelectList, BoxOutline, TextBox},
+    study,
+    vec2::Vec2,
+};
+
+use super::MIN_TERM_SIZE;
+
+/// Lets the user browse through `cards`, showing both sides of the selected one,
+/// until they press escape
+pub fn examine(cards: &[Flashcard], term_size: Vec2<u16>, events: &mut Events) {
+    let texts: Vec<_> = cards
+        .iter()
+        .map(|card| [Side::Term, Side::Definition].map(|side| card[side].displayable().join(", ")))
+        .collect();
+    let mut examiner = Examiner {
+        list: SelectList::new(texts.iter().map(|[term, _]| term.as_str()).collect()),
+        side_boxes: [Side::Term, Side::Definition].map(|side| {
+            let mut side_box = TextBox::new();
+            side_box
+                .outline(Some(BoxOutline::HEAVY))
+                .color(side.color());
+            side_box
+        }),
+    };
+
+    examiner.resize_to(term_size).draw(cards, &texts);
+    io::stdout().flush().unwrap();
+    loop {
+        match events.read() {
+            crate::esc!() => break,
+            Event::Resize(w, h) => {
+                queue!(io::stdout(), terminal::Clear(ClearType::All)).unwrap();
+                let term_size = study::wait_for_size(events, Vec2::new(w, h), MIN_TERM_SIZE);
+                examiner.resize_to(term_size).draw(cards, &texts);
+            }
+            event => {
+                let old_selected = examiner.list.selected();
+                examiner.list.update(&event);
+                let selected = examiner.list.selected();
+                if selected != old_selected {
+                    for (side_box, (old_text, text)) in examiner
+                        .side_boxes
+                        .iter_mut()
+                        .zip(texts[old_selected].iter().zip(&texts[selected]))
+                    {
+                        side_box
+                            .wrap(cards[old_selected].wrap)
+                            .overwrite_text(old_text, "");
+                        side_box
+                            .wrap(cards[selected].wrap)
+                            .draw_outline()
+                            .draw_text(text);
+                        mark_truncated(side_box);
+                    }
+                }
+            }
+        }
+        io::stdout().flush().unwrap();
+    }
+}
+
+#[derive(Debug)]
+struct Examiner<'a> {
+    list: SelectList<&'a str>,
+    /// Shows the term and definition of the selected card
+    side_boxes: [TextBox; 2],
+}
+
+impl Examiner<'_> {
+    fn resize_to(&mut self, term_size: Vec2<u16>) -> &mut Self {
+        let list_width = term_size.x / 3;
+        let box_height = term_size.y / 2;
+        self.list.size(Vec2::new(list_width, term_size.y));
+        self.side_boxes[0]
+            .pos(Vec2::new(list_width, 0))
+            .size(Vec2::new(term_size.x - list_width, box_height));
+        self.side_boxes[1]
+            .pos(Vec2::new(list_width, box_height))
+            .size(Vec2::new(
+                term_size.x - list_width,
+                term_size.y - box_height,
+            ));
+        self
+    }
+
+    fn draw(&mut self, cards: &[Flashcard], texts: &[[String; 2]]) -> &mut Self {
+        self.list.draw();
+        let selected = self.list.selected();
+        for (side_box, text) in self.side_boxes.iter_mut().zip(&texts[selected]) {
+            side_box
+                .wrap(cards[selected].wrap)
+                .draw_outline_and_text(text);
+            mark_truncated(side_box);
+        }
+        self
+    }
+}
+
+/// Notes on the bottom of `side_box`'s outline if its text didn't all fit, so it's
+/// clear the card says more than is shown
+fn mark_truncated(side_box: &TextBox) {
+    if side_box.is_truncated() {
+        queue!(
+            io::stdout(),
+            side_box
+                .pos
+                .map_x(|x| x + 2)
+                .map_y(|y| y + side_box.size.y - 1)
+                .move_to(),
+            style::SetForegroundColor(side_box.outline_color),
+            style::Print(" cut off "),
+            style::ResetColor
+        )
+        .unwrap();
+    }
+}
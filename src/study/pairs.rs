@@ -0,0 +1,269 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use argh::FromArgs;
+use crossterm::{
+    event::{Event, KeyCode, KeyEvent},
+    queue,
+    style::Color,
+    terminal::{self, ClearType},
+};
+use rand::seq::SliceRandom;
+
+use crate::{
+    flashcards::{Flashcard, Set, Side},
+    input::events::Events,
+    load_set,
+    output::{self, text_box::BoxOutline, TerminalSettings, TextBox},
+    vec2::Vec2,
+};
+
+/// Match terms to their definitions, a few cards at a time
+#[derive(FromArgs, Debug)]
+#[argh(subcommand, name = "pairs")]
+pub struct Entry {
+    /// the set to study
+    #[argh(positional)]
+    set: PathBuf,
+    /// treat `set` as a url and download it (requires the "fetch" feature)
+    #[argh(switch)]
+    fetch: bool,
+    /// refuse to start if the set has warnings, like empty values or repeated terms
+    #[argh(switch)]
+    strict: bool,
+    /// show the set's title as the terminal window's title, which is left there after
+    /// exiting since terminals can't report their title
+    #[argh(switch)]
+    window_title: bool,
+    /// how many pairs to show at once, from 2 to 9 (default 4)
+    #[argh(option, default = "4", from_str_fn(parse_count))]
+    count: usize,
+    /// record every input to a file so the session can be replayed
+    #[argh(option)]
+    record: Option<PathBuf>,
+    /// replay inputs recorded with --record before reading from the terminal
+    #[argh(option)]
+    replay: Option<PathBuf>,
+    /// stay out of raw mode and the alternate screen, and log every input to stderr,
+    /// so errors and events can be seen while debugging
+    #[argh(switch)]
+    no_raw_on_error: bool,
+}
+
+fn parse_count(s: &str) -> Result<usize, String> {
+    match s.parse::<usize>().map_err(|e| e.to_string())? {
+        count @ 2..=9 => Ok(count),
+        _ => Err("Count must be from 2 to 9".to_owned()),
+    }
+}
+
+impl Entry {
+    pub fn run(self) {
+        let set = load_set!(&self.set, fetch: self.fetch);
+        if self.strict {
+            super::exit_if_problems(&set, false);
+        }
+        let count = self.count.min(set.cards.len());
+        if count < 2 {
+            output::write_fatal_error("Set must have at least 2 cards to match pairs");
+            return;
+        }
+        let min_size = Vec2::new(20, Round::ROW_HEIGHT * count as u16 + 1);
+        let mut term_size = match output::terminal_size_at_least(min_size) {
+            Some(size) => size,
+            None => return,
+        };
+        let mut events = match Events::open(self.record.as_deref(), self.replay.as_deref()) {
+            Some(events) => events,
+            None => return,
+        };
+        events.log = self.no_raw_on_error;
+
+        let mut term_settings = TerminalSettings::new();
+        if !self.no_raw_on_error {
+            term_settings
+                .enter_alternate_screen()
+                .hide_cursor()
+                .enable_raw_mode();
+        }
+        if let (true, Some(title)) = (self.window_title, &set.metadata.title) {
+            term_settings.set_title(title);
+        }
+
+        let mut rng = rand::thread_rng();
+        let mut score = Score::default();
+        loop {
+            let cards = set
+                .cards
+                .choose_multiple(&mut rng, count)
+                .collect::<Vec<_>>();
+            let mut round = Round::new(cards, &mut rng);
+            loop {
+                round.draw(term_size, score);
+                match events.read() {
+                    crate::esc!() => return,
+                    Event::Resize(w, h) => {
+                        term_size = super::wait_for_size(&mut events, Vec2::new(w, h), min_size)
+                    }
+                    crate::pause!() => {
+                        let size = super::pause(&mut events);
+                        term_size = super::wait_for_size(&mut events, size, min_size);
+                    }
+                    Event::Key(_) if round.is_scored() => break,
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char(c),
+                        ..
+                    }) => {
+                        round.press(c);
+                        if round.is_scored() {
+                            score.correct += round.correct_count();
+                            score.total += round.terms.len();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Score {
+    correct: usize,
+    total: usize,
+}
+
+/// One screen of terms to pair with shuffled definitions.  Terms are chosen with
+/// `1`-`9` and definitions with `a`-`i`
+#[derive(Debug)]
+struct Round<'a> {
+    /// The text shown for each card's term, chosen once so it doesn't change when
+    /// the round is redrawn
+    terms: Vec<&'a str>,
+    /// The text shown for each card's definition, chosen the same way
+    definitions: Vec<&'a str>,
+    /// The card whose definition is shown in each row of the right column
+    order: Vec<usize>,
+    /// The row of the definition each term is paired with
+    pairs: Vec<Option<usize>>,
+    selected: Option<usize>,
+}
+
+impl<'a> Round<'a> {
+    const ROW_HEIGHT: u16 = 3;
+
+    fn new(cards: Vec<&'a Flashcard>, rng: &mut impl rand::Rng) -> Self {
+        let mut order = (0..cards.len()).collect::<Vec<_>>();
+        order.shuffle(rng);
+        let mut texts = |side| {
+            cards
+                .iter()
+                .map(|card| card[side].display_with(&mut *rng))
+                .collect()
+        };
+        Self {
+            terms: texts(Side::Term),
+            definitions: texts(Side::Definition),
+            pairs: vec![None; cards.len()],
+            order,
+            selected: None,
+        }
+    }
+
+    fn press(&mut self, c: char) {
+        let len = self.terms.len() as u32;
+        if let Some(term) = c.to_digit(10).filter(|d| (1..=len).contains(d)) {
+            self.selected = Some(term as usize - 1);
+        } else if let Some(row) = (c as u32).checked_sub('a' as u32).filter(|row| *row < len) {
+            if let Some(term) = self.selected.take() {
+                let row = row as usize;
+                // Each definition can only be paired with one term
+                for pair in &mut self.pairs {
+                    if *pair == Some(row) {
+                        *pair = None;
+                    }
+                }
+                self.pairs[term] = Some(row);
+            }
+        }
+    }
+
+    fn is_scored(&self) -> bool {
+        self.pairs.iter().all(Option::is_some)
+    }
+
+    fn is_correct(&self, term: usize) -> bool {
+        self.pairs[term].map(|row| self.order[row]) == Some(term)
+    }
+
+    fn correct_count(&self) -> usize {
+        (0..self.terms.len())
+            .filter(|term| self.is_correct(*term))
+            .count()
+    }
+
+    fn draw(&self, term_size: Vec2<u16>, score: Score) {
+        queue!(io::stdout(), terminal::Clear(ClearType::All)).unwrap();
+
+        let status = match self.is_scored() {
+            true => format!(
+                "{}/{} correct - press any key to continue",
+                self.correct_count(),
+                self.terms.len()
+            ),
+            false => format!(
+                "Pair each number with a letter   Score: {}/{}",
+                score.correct, score.total
+            ),
+        };
+        TextBox::new()
+            .outline(None)
+            .size(Vec2::new(term_size.x, 1))
+            .draw_text(&status);
+
+        let row_height = ((term_size.y - 1) / self.terms.len() as u16).max(Self::ROW_HEIGHT);
+        let column_width = term_size.x / 2;
+        let mut text_box = TextBox::new();
+        text_box
+            .outline(Some(BoxOutline::DOUBLE))
+            .size(Vec2::new(column_width, row_height));
+
+        for (term, text) in self.terms.iter().enumerate() {
+            let paired = self.pairs[term];
+            let color = match (self.is_scored(), paired) {
+                (true, _) if self.is_correct(term) => Color::Green,
+                (true, _) => Color::Red,
+                (false, Some(_)) => Color::DarkGrey,
+                (false, None) if self.selected == Some(term) => Color::Yellow,
+                (false, None) => Color::White,
+            };
+            let label = match paired {
+                Some(row) => format!(" -> {}", (b'a' + row as u8) as char),
+                None => String::new(),
+            };
+            text_box
+                .pos(Vec2::new(0, 1 + row_height * term as u16))
+                .color(color)
+                .draw_outline_and_text(&format!("{}. {text}{label}", term + 1));
+        }
+
+        for (row, &term) in self.order.iter().enumerate() {
+            let paired = self.pairs.contains(&Some(row));
+            text_box
+                .pos(Vec2::new(column_width, 1 + row_height * row as u16))
+                .color(match paired {
+                    true => Color::DarkGrey,
+                    false => Color::White,
+                })
+                .draw_outline_and_text(&format!(
+                    "{}. {}",
+                    (b'a' + row as u8) as char,
+                    self.definitions[term]
+                ));
+        }
+
+        io::stdout().flush().unwrap();
+    }
+}
@@ -77,6 +77,18 @@ impl Vec2<u16> {
     pub fn move_to(self) -> MoveTo {
         MoveTo(self.x, self.y)
     }
+
+    /// Adds each component, stopping at `u16::MAX` instead of overflowing
+    #[must_use]
+    pub fn saturating_add(self, other: Vec2<u16>) -> Vec2<u16> {
+        self.join(other, u16::saturating_add)
+    }
+
+    /// Subtracts each component, stopping at 0 instead of underflowing
+    #[must_use]
+    pub fn saturating_sub(self, other: Vec2<u16>) -> Vec2<u16> {
+        self.join(other, u16::saturating_sub)
+    }
 }
 
 impl<T: Copy> IntoIterator for Vec2<T> {